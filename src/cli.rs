@@ -0,0 +1,112 @@
+use crate::App;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: ratatui_game_of_life [OPTIONS] [PATTERN]
+
+Arguments:
+  [PATTERN]           .rle or .cells file to start with instead of a random board
+
+Options:
+  --width <cells>     Grid width, instead of the terminal width
+  --height <cells>    Grid height, instead of the terminal height
+  --ups <n>           Maximum updates per second, fractions allowed (alias: --fps)
+  --seed <n>          Seed of the initial random board
+  --png-scale <n>     Pixels per cell in exported PNG images
+  --bench <n>         Run n generations without a terminal and print the update speed
+  --autosave <secs>   Save the board to autosave.rle every secs seconds
+  --resume            Start from autosave.rle instead of a random board
+  --generations <n>   Pause when generation n is reached
+  --paused            Start paused, to edit the board first
+  --confirm-new-soup  Ask for <N> twice before replacing the board
+  --dump-on-exit      Print the final board to stdout as # and . rows on quit
+  -h, --help          Print this help";
+
+#[derive(Default)]
+pub struct Args {
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub update_per_second: Option<f64>,
+    pub seed: Option<u64>,
+    pub png_scale: Option<u32>,
+    pub bench: Option<u64>,
+    pub autosave_interval: Option<Duration>,
+    pub resume: bool,
+    pub generations: Option<u64>,
+    pub paused: bool,
+    pub confirm_new_soup: bool,
+    pub dump_on_exit: bool,
+    pub help: bool,
+    pub pattern: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--width" => parsed.width = Some(parse_grid_size(&arg, args.next())?),
+                "--height" => parsed.height = Some(parse_grid_size(&arg, args.next())?),
+                "--ups" | "--fps" => {
+                    let update_per_second: f64 = parse_value(&arg, args.next())?;
+                    if !(App::MIN_UPDATE_PER_SECOND..=App::MAX_UPDATE_PER_SECOND).contains(&update_per_second) {
+                        return Err(format!(
+                            "{arg} must be between {} and {}",
+                            App::MIN_UPDATE_PER_SECOND,
+                            App::MAX_UPDATE_PER_SECOND
+                        ));
+                    }
+                    parsed.update_per_second = Some(update_per_second);
+                }
+                "--seed" => parsed.seed = Some(parse_value(&arg, args.next())?),
+                "--png-scale" => {
+                    let png_scale: u32 = parse_value(&arg, args.next())?;
+                    if !(1..=App::MAX_PNG_SCALE).contains(&png_scale) {
+                        return Err(format!("{arg} must be between 1 and {}", App::MAX_PNG_SCALE));
+                    }
+                    parsed.png_scale = Some(png_scale);
+                }
+                "--bench" => {
+                    let generations: u64 = parse_value(&arg, args.next())?;
+                    if generations == 0 {
+                        return Err(format!("{arg} must be at least 1"));
+                    }
+                    parsed.bench = Some(generations);
+                }
+                "--autosave" => {
+                    let seconds: u64 = parse_value(&arg, args.next())?;
+                    if seconds == 0 {
+                        return Err(format!("{arg} must be at least 1"));
+                    }
+                    parsed.autosave_interval = Some(Duration::from_secs(seconds));
+                }
+                "--resume" => parsed.resume = true,
+                "--paused" => parsed.paused = true,
+                "--confirm-new-soup" => parsed.confirm_new_soup = true,
+                "--dump-on-exit" => parsed.dump_on_exit = true,
+                "--generations" => parsed.generations = Some(parse_value(&arg, args.next())?),
+                "-h" | "--help" => parsed.help = true,
+                _ if !arg.starts_with('-') && parsed.pattern.is_none() => parsed.pattern = Some(arg.into()),
+                _ => return Err(format!("unknown argument '{arg}'")),
+            }
+        }
+        if parsed.resume && parsed.pattern.is_some() {
+            return Err("--resume cannot be combined with a PATTERN".to_string());
+        }
+        Ok(parsed)
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{flag} needs a value"))?;
+    value.parse().map_err(|_| format!("invalid value '{value}' for {flag}"))
+}
+
+fn parse_grid_size(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let size = parse_value(flag, value)?;
+    if !(App::MIN_GRID_SIZE..=App::MAX_GRID_SIZE).contains(&size) {
+        return Err(format!("{flag} must be between {} and {}", App::MIN_GRID_SIZE, App::MAX_GRID_SIZE));
+    }
+    Ok(size)
+}
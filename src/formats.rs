@@ -0,0 +1,263 @@
+use crate::life::{GameTable, ALIVE, DEAD};
+use std::io;
+use std::path::Path;
+
+/// RLE lines are kept under this length, as most Life software expects.
+const RLE_LINE_LENGTH: usize = 70;
+/// Largest pattern area an RLE header may declare, so a bad header can't
+/// allocate without bound.
+const MAX_PATTERN_CELLS: usize = 1 << 24;
+
+/// Encodes a table in the standard Life RLE format.
+///
+/// Dead cells at the end of a row and empty rows at the end of the table
+/// are left out, as the format allows. Dying cells of a Generations rule
+/// are written as dead.
+pub fn rle_encode(game_table: &GameTable, rule: &str) -> String {
+    let height = game_table.len();
+    let width = game_table.first().map_or(0, Vec::len);
+
+    let mut tokens: Vec<String> = Vec::new();
+    let mut pending_rows = 0;
+    for row in game_table {
+        let last_alive = match row.iter().rposition(|cell| *cell == ALIVE) {
+            Some(last_alive) => last_alive,
+            None => {
+                pending_rows += 1;
+                continue;
+            }
+        };
+        if !tokens.is_empty() {
+            tokens.push(rle_run(pending_rows + 1, '$'));
+        } else if pending_rows > 0 {
+            tokens.push(rle_run(pending_rows, '$'));
+        }
+        pending_rows = 0;
+
+        let mut cells = row[..=last_alive].iter().peekable();
+        while let Some(cell) = cells.next() {
+            let mut count = 1;
+            let alive = *cell == ALIVE;
+            while cells.next_if(|cell| (**cell == ALIVE) == alive).is_some() {
+                count += 1;
+            }
+            tokens.push(rle_run(count, if alive { 'o' } else { 'b' }));
+        }
+    }
+    tokens.push("!".to_string());
+
+    let mut rle = format!("x = {width}, y = {height}, rule = {rule}\n");
+    let mut line_length = 0;
+    for token in tokens {
+        if line_length + token.len() > RLE_LINE_LENGTH {
+            rle.push('\n');
+            line_length = 0;
+        }
+        line_length += token.len();
+        rle.push_str(&token);
+    }
+    rle.push('\n');
+    rle
+}
+
+fn rle_run(count: usize, tag: char) -> String {
+    if count == 1 {
+        tag.to_string()
+    } else {
+        format!("{count}{tag}")
+    }
+}
+
+/// Decodes an RLE pattern into a table of the size given by its header.
+///
+/// `#` comment lines are skipped. Cells past the declared size are dropped.
+pub fn rle_decode(input: &str) -> io::Result<GameTable> {
+    let mut lines = input.lines().map(str::trim).filter(|line| !line.starts_with('#') && !line.is_empty());
+    let header = lines.next().ok_or_else(|| invalid_pattern("missing RLE header"))?;
+    let (width, height) = parse_rle_header(header)?;
+
+    let mut game_table = vec![vec![DEAD; width]; height];
+    let (mut x, mut y): (usize, usize) = (0, 0);
+    let mut count: Option<usize> = None;
+    'tokens: for line in lines {
+        for character in line.chars() {
+            match character {
+                '0'..='9' => {
+                    let digit = character.to_digit(10).unwrap() as usize;
+                    let next = count.unwrap_or(0).checked_mul(10).and_then(|count| count.checked_add(digit));
+                    count = Some(next.ok_or_else(|| invalid_pattern("RLE run count is too large"))?);
+                    continue;
+                }
+                'b' | '.' => y = y.saturating_add(count.unwrap_or(1)),
+                'o' | 'A'..='Z' => {
+                    let end = y.saturating_add(count.unwrap_or(1));
+                    if x < height && y < width {
+                        game_table[x][y..end.min(width)].fill(ALIVE);
+                    }
+                    y = end;
+                }
+                '$' => {
+                    x = x.saturating_add(count.unwrap_or(1));
+                    y = 0;
+                }
+                '!' => break 'tokens,
+                character if character.is_whitespace() => {}
+                character => return Err(invalid_pattern(&format!("unexpected '{character}' in RLE"))),
+            }
+            count = None;
+        }
+    }
+
+    Ok(game_table)
+}
+
+fn parse_rle_header(header: &str) -> io::Result<(usize, usize)> {
+    let mut width = None;
+    let mut height = None;
+    // The rule comes last and may itself hold commas, as in `B3,10-12/S2`.
+    let dimensions = header.split_once("rule").map_or(header, |(dimensions, _)| dimensions);
+    for field in dimensions.split(',').filter(|field| !field.trim().is_empty()) {
+        let (key, value) = field.split_once('=').ok_or_else(|| invalid_pattern("bad RLE header"))?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = value.parse::<usize>().ok(),
+            "y" => height = value.parse::<usize>().ok(),
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) if width.checked_mul(height).is_some_and(|cells| cells <= MAX_PATTERN_CELLS) => {
+            Ok((width, height))
+        }
+        (Some(_), Some(_)) => Err(invalid_pattern(&format!("RLE patterns are limited to {MAX_PATTERN_CELLS} cells"))),
+        _ => Err(invalid_pattern("RLE header needs x and y")),
+    }
+}
+
+/// Parses a plaintext `.cells` pattern: `.` is dead, `O` is alive and
+/// lines starting with `!` are comments. Short rows are padded with dead
+/// cells up to the widest row.
+pub fn parse_cells(input: &str) -> io::Result<GameTable> {
+    let mut game_table: GameTable = Vec::new();
+    for line in input.lines().filter(|line| !line.starts_with('!')) {
+        let mut row = Vec::new();
+        for character in line.trim_end().chars() {
+            match character {
+                '.' => row.push(DEAD),
+                'O' => row.push(ALIVE),
+                character if character.is_whitespace() => {}
+                character => return Err(invalid_pattern(&format!("unexpected '{character}' in .cells file"))),
+            }
+        }
+        game_table.push(row);
+    }
+
+    let width = game_table.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut game_table {
+        row.resize(width, DEAD);
+    }
+
+    Ok(game_table)
+}
+
+/// Rows and columns of the smallest rectangle holding every live cell, as
+/// inclusive `((row_min, column_min), (row_max, column_max))`.
+pub fn bounding_box(game_table: &GameTable) -> Option<((usize, usize), (usize, usize))> {
+    let rows: Vec<usize> = (0..game_table.len()).filter(|x| game_table[*x].contains(&ALIVE)).collect();
+    let (&x_min, &x_max) = (rows.first()?, rows.last()?);
+    let y_min = rows.iter().filter_map(|x| game_table[*x].iter().position(|cell| *cell == ALIVE)).min()?;
+    let y_max = rows.iter().filter_map(|x| game_table[*x].iter().rposition(|cell| *cell == ALIVE)).max()?;
+    Some(((x_min, y_min), (x_max, y_max)))
+}
+
+/// Writes the live cells' bounding box as plaintext rows of `alive` and
+/// `dead` characters, after a `!` comment line giving its size. With `O`
+/// and `.` this is a `.cells` file.
+pub fn plaintext_encode(game_table: &GameTable, alive: char, dead: char) -> Option<String> {
+    let ((x_min, y_min), (x_max, y_max)) = bounding_box(game_table)?;
+    let pattern: GameTable = game_table[x_min..=x_max].iter().map(|row| row[y_min..=y_max].to_vec()).collect();
+    let header = format!("! {}x{}\n", y_max - y_min + 1, x_max - x_min + 1);
+    Some(header + &plaintext_rows(&pattern, alive, dead))
+}
+
+/// Writes every row of the table, dead edges included, as plaintext rows
+/// of `alive` and `dead` characters.
+pub fn plaintext_rows(game_table: &GameTable, alive: char, dead: char) -> String {
+    let mut text = String::new();
+    for row in game_table {
+        text.extend(row.iter().map(|cell| if *cell == ALIVE { alive } else { dead }));
+        text.push('\n');
+    }
+    text
+}
+
+/// Reads an RLE (`.rle`) or plaintext (`.cells`) pattern file, picking the
+/// format from the extension.
+pub fn read_pattern_file(path: &Path) -> io::Result<GameTable> {
+    let input = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("rle") => rle_decode(&input),
+        Some("cells") => parse_cells(&input),
+        _ => Err(invalid_pattern("pattern files must end in .rle or .cells")),
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path`, then renames it
+/// over `path`, so readers only ever see the old or the new file whole.
+pub fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    std::fs::write(&temporary, contents)?;
+    std::fs::rename(&temporary, path)
+}
+
+fn invalid_pattern(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rle_header_keeps_a_rule_with_commas() {
+        let mut game_table = vec![vec![DEAD; 6]; 4];
+        game_table[1][2] = ALIVE;
+        let rle = rle_encode(&game_table, "B3,10-12/S2");
+        assert!(rle.starts_with("x = 6, y = 4, rule = B3,10-12/S2\n"));
+        assert_eq!(rle_decode(&rle).unwrap(), game_table);
+    }
+
+    #[test]
+    fn rle_round_trips_boards() {
+        let mut glider = vec![vec![DEAD; 5]; 5];
+        for (x, y) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            glider[x][y] = ALIVE;
+        }
+        // Runs past RLE_LINE_LENGTH and empty rows at both ends.
+        let mut wide = vec![vec![DEAD; 150]; 6];
+        wide[2][3..140].fill(ALIVE);
+        wide[3].iter_mut().step_by(2).for_each(|cell| *cell = ALIVE);
+        for game_table in [glider, wide, vec![vec![DEAD; 4]; 3]] {
+            let rle = rle_encode(&game_table, "B3/S23");
+            assert!(rle.lines().all(|line| line.len() <= RLE_LINE_LENGTH));
+            assert_eq!(rle_decode(&rle).unwrap(), game_table);
+        }
+    }
+
+    #[test]
+    fn rle_rejects_huge_counts_and_sizes() {
+        assert!(rle_decode("x = 3, y = 3\n99999999999999999999999o!").is_err());
+        assert!(rle_decode("x = 100000, y = 100000\no!").is_err());
+        assert!(rle_decode("x = 99999999999999999999, y = 1\no!").is_err());
+        assert_eq!(rle_decode("x = 3, y = 1\n9999999o!").unwrap(), vec![vec![ALIVE; 3]]);
+    }
+
+    #[test]
+    fn cells_files_reject_unknown_characters() {
+        assert_eq!(parse_cells("!comment\n.O\nOO.\n").unwrap(), vec![vec![DEAD, ALIVE, DEAD], vec![ALIVE, ALIVE, DEAD]]);
+        let error = parse_cells(".O\n.x\n").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("'x'"));
+    }
+}
@@ -0,0 +1,486 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+
+/// Cell state: `DEAD`, `ALIVE`, or from 2 up the dying states of a
+/// Generations rule, which count up to `Rule::states` and back to dead.
+pub type Cell = u8;
+pub const DEAD: Cell = 0;
+pub const ALIVE: Cell = 1;
+
+pub type GameTable = Vec<Vec<Cell>>;
+
+/// Largest neighbourhood range a rule can be used with.
+pub const MAX_RANGE: i32 = 5;
+
+/// The cells of a generation, with their `(height, width)`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Board {
+    pub cells: GameTable,
+    pub size: (usize, usize),
+}
+
+impl Board {
+    pub fn empty(size: (usize, usize)) -> Board {
+        Board { cells: initialize_empty_game_table(size), size }
+    }
+
+    pub fn random(size: (usize, usize), seed: u64, density: f64) -> Board {
+        Board { cells: initialize_game_table(size, seed, density), size }
+    }
+
+    /// Wraps `cells`, taking the size from the table itself.
+    #[cfg(test)]
+    pub fn from_cells(cells: GameTable) -> Board {
+        let size = (cells.len(), cells.first().map_or(0, Vec::len));
+        Board { cells, size }
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().map(|row| row.iter().filter(|cell| **cell == ALIVE).count()).sum()
+    }
+
+    /// Maps a possibly out-of-range neighbour position onto the board,
+    /// or returns `None` when it falls off a `Boundary::Dead` edge.
+    pub fn neighbour_position(&self, boundary: Boundary, x: i32, y: i32) -> Option<(usize, usize)> {
+        let height = self.size.0 as i32;
+        let width = self.size.1 as i32;
+        match boundary {
+            Boundary::Toroidal => Some((x.rem_euclid(height) as usize, y.rem_euclid(width) as usize)),
+            Boundary::Dead if (0..height).contains(&x) && (0..width).contains(&y) => Some((x as usize, y as usize)),
+            Boundary::Dead => None,
+        }
+    }
+
+    /// Live cells up to `range` rows and columns away from `(x, y)`. Cells
+    /// set in `walls`, when given, count as alive.
+    pub fn count_number_of_neighbour(
+        &self,
+        x: usize,
+        y: usize,
+        range: i32,
+        boundary: Boundary,
+        walls: Option<&[Vec<bool>]>,
+    ) -> u8 {
+        let xi32 = x as i32;
+        let yi32 = y as i32;
+        let mut count = 0;
+        for iy in -range..=range {
+            for ix in -range..=range {
+                if iy == 0 && ix == 0 { continue };
+                let Some((real_x, real_y)) = self.neighbour_position(boundary, xi32 + ix, yi32 + iy) else {
+                    continue;
+                };
+
+                if self.cells[real_x][real_y] == ALIVE || walls.is_some_and(|walls| walls[real_x][real_y]) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Fills `counts` with every cell's `count_number_of_neighbour`, in one
+    /// pass over the board: each live cell (or wall) adds one to the cells
+    /// around it. The wrapped row and column of every offset are looked up
+    /// in tables built once per call, so the inner loop has no modulo.
+    pub fn neighbour_counts(
+        &self,
+        counts: &mut Vec<Vec<u8>>,
+        range: i32,
+        boundary: Boundary,
+        walls: Option<&[Vec<bool>]>,
+    ) {
+        let (height, width) = self.size;
+        if counts.len() != height || counts.first().map(Vec::len) != Some(width) {
+            *counts = vec![vec![0; width]; height];
+        }
+        counts.iter_mut().for_each(|row| row.fill(0));
+
+        let range_usize = range as usize;
+        let rows = wrapped_indices(height, range, boundary);
+        let columns = wrapped_indices(width, range, boundary);
+
+        for (x, row) in self.cells.iter().enumerate() {
+            for (y, cell) in row.iter().enumerate() {
+                if *cell != ALIVE && !walls.is_some_and(|walls| walls[x][y]) {
+                    continue;
+                }
+                for (dx, real_x) in rows[x..=x + 2 * range_usize].iter().enumerate() {
+                    let Some(real_x) = real_x else { continue };
+                    let counts_row = &mut counts[*real_x];
+                    for (dy, real_y) in columns[y..=y + 2 * range_usize].iter().enumerate() {
+                        let Some(real_y) = real_y else { continue };
+                        if dx != range_usize || dy != range_usize {
+                            counts_row[*real_y] += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Computes the next generation into `back`, one `next_cell(self, x, y)`
+    /// call per cell, all reading this board. `back` is only reallocated
+    /// when its size differs, so steady-state steps do not allocate.
+    pub fn update_game_table(&self, back: &mut Board, mut next_cell: impl FnMut(&Board, usize, usize) -> Cell) {
+        if back.size != self.size {
+            *back = Board::empty(self.size);
+        }
+
+        for (x, row) in back.cells.iter_mut().enumerate() {
+            for (y, new_cell_state) in row.iter_mut().enumerate() {
+                *new_cell_state = next_cell(self, x, y);
+            }
+        }
+    }
+
+    /// `neighbour_counts` computed row by row on rayon tasks. Scattering
+    /// does not split into independent rows, so each row first sums its
+    /// live cells across the window of columns, then every count adds up
+    /// those sums over the window of rows.
+    #[cfg(feature = "parallel")]
+    pub fn par_neighbour_counts(
+        &self,
+        counts: &mut Vec<Vec<u8>>,
+        range: i32,
+        boundary: Boundary,
+        walls: Option<&[Vec<bool>]>,
+    ) {
+        let (height, width) = self.size;
+        if counts.len() != height || counts.first().map(Vec::len) != Some(width) {
+            *counts = vec![vec![0; width]; height];
+        }
+
+        let window = 2 * range as usize;
+        let rows = wrapped_indices(height, range, boundary);
+        let columns = wrapped_indices(width, range, boundary);
+        let occupied = |x: usize, y: usize| self.cells[x][y] == ALIVE || walls.is_some_and(|walls| walls[x][y]);
+        let row_sums: Vec<Vec<u8>> = (0..height)
+            .into_par_iter()
+            .map(|x| {
+                (0..width)
+                    .map(|y| columns[y..=y + window].iter().flatten().filter(|real_y| occupied(x, **real_y)).count() as u8)
+                    .collect()
+            })
+            .collect();
+
+        counts.par_iter_mut().enumerate().for_each(|(x, row)| {
+            for (y, count) in row.iter_mut().enumerate() {
+                let sum: u8 = rows[x..=x + window].iter().flatten().map(|real_x| row_sums[*real_x][y]).sum();
+                *count = sum - u8::from(occupied(x, y));
+            }
+        });
+    }
+
+    /// `update_game_table` with one rayon task per row of `back`.
+    #[cfg(feature = "parallel")]
+    pub fn par_update_game_table(&self, back: &mut Board, next_cell: impl Fn(&Board, usize, usize) -> Cell + Sync) {
+        if back.size != self.size {
+            *back = Board::empty(self.size);
+        }
+
+        back.cells.par_iter_mut().enumerate().for_each(|(x, row)| {
+            for (y, new_cell_state) in row.iter_mut().enumerate() {
+                *new_cell_state = next_cell(self, x, y);
+            }
+        });
+    }
+
+    /// The next generation under `rule`, in the classic neighbourhood.
+    #[cfg(test)]
+    pub fn step(&self, rule: &Rule, boundary: Boundary) -> Board {
+        let mut counts = Vec::new();
+        self.neighbour_counts(&mut counts, 1, boundary, None);
+        let mut next = Board::empty(self.size);
+        self.update_game_table(&mut next, |board, x, y| rule.next_cell(board.cells[x][y], counts[x][y]));
+        next
+    }
+}
+
+/// What lies beyond the edges of the board.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Boundary {
+    /// Opposite edges are joined, so the board is a torus.
+    #[default]
+    Toroidal,
+    /// Everything outside the board counts as dead.
+    Dead,
+}
+
+impl Boundary {
+    pub fn name(self) -> &'static str {
+        match self {
+            Boundary::Toroidal => "toroidal",
+            Boundary::Dead => "dead",
+        }
+    }
+}
+
+/// Live-neighbour count table, indexed by count.
+pub type NeighbourTable = [bool; Rule::MAX_COUNT + 1];
+
+/// Birth/survival rule in B/S notation, e.g. `B3/S23` for Conway's Life.
+///
+/// `birth[n]` tells whether a dead cell with `n` live neighbours is born,
+/// `survival[n]` whether a live one stays alive. The tables go past 8 so
+/// that rules for a neighbourhood `range` above 1 can be expressed.
+///
+/// With `states` above 2 this is a Generations rule (`B2/S/C3` is Brian's
+/// Brain): a live cell that does not survive goes through the dying states
+/// instead of dying at once. Dying cells are not counted as neighbours and
+/// cannot be born into.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Rule {
+    birth: NeighbourTable,
+    survival: NeighbourTable,
+    pub states: u8,
+}
+
+impl Rule {
+    /// Neighbour count of a full neighbourhood at `MAX_RANGE`.
+    pub const MAX_COUNT: usize = ((2 * MAX_RANGE + 1).pow(2) - 1) as usize;
+
+    pub const CONWAY: Rule = Rule {
+        birth: neighbour_table(&[3]),
+        survival: neighbour_table(&[2, 3]),
+        states: 2,
+    };
+
+    /// Parses a `B<counts>/S<counts>` rulestring, in either order and case.
+    ///
+    /// Counts are single digits as in `B3/S23`, or, to go past 9, a comma
+    /// separated list of counts and `min-max` ranges as in `B34-45/S34-58`.
+    /// An optional `C<states>` part makes it a Generations rule.
+    pub fn parse(rulestring: &str) -> Result<Rule, String> {
+        let parts: Vec<&str> = rulestring.trim().split('/').collect();
+        if parts.len() < 2 {
+            return Err(format!("rule '{rulestring}' is missing a '/'"));
+        }
+
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = 2;
+        for part in parts {
+            let mut characters = part.chars();
+            let counts = match characters.next().map(|prefix| prefix.to_ascii_uppercase()) {
+                Some('B') => &mut birth,
+                Some('S') => &mut survival,
+                Some('C') => {
+                    states = match characters.as_str().parse() {
+                        Ok(states @ 2..) => states,
+                        _ => return Err(format!("'{part}' needs a state count from 2 to {}", u8::MAX)),
+                    };
+                    continue;
+                }
+                _ => return Err(format!("rule part '{part}' must start with B, S or C")),
+            };
+            let body = characters.as_str();
+            let mut table = [false; Rule::MAX_COUNT + 1];
+            if body.contains([',', '-']) {
+                for item in body.split(',').filter(|item| !item.is_empty()) {
+                    let (min, max) = item.split_once('-').unwrap_or((item, item));
+                    let (min, max) = (parse_neighbour_count(min)?, parse_neighbour_count(max)?);
+                    table[min..=max.max(min)].fill(true);
+                }
+            } else {
+                for digit in body.chars() {
+                    match digit.to_digit(10) {
+                        Some(count) => table[count as usize] = true,
+                        None => return Err(format!("'{digit}' is not a neighbour count (0-9)")),
+                    }
+                }
+            }
+            *counts = Some(table);
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Rule { birth, survival, states }),
+            _ => Err(format!("rule '{rulestring}' needs one B part and one S part")),
+        }
+    }
+
+    pub fn next_state(&self, neighbour: u8, alive: bool) -> bool {
+        let table = if alive { &self.survival } else { &self.birth };
+        table[neighbour as usize]
+    }
+
+    /// State after `cell` for a live cell that did not survive or a dying
+    /// one: the next dying state, or dead after the last.
+    pub fn decay(&self, cell: Cell) -> Cell {
+        if cell + 1 < self.states { cell + 1 } else { DEAD }
+    }
+
+    /// Next state of `cell` with `neighbour` live neighbours.
+    pub fn next_cell(&self, cell: Cell, neighbour: u8) -> Cell {
+        match cell {
+            DEAD if self.next_state(neighbour, false) => ALIVE,
+            DEAD => DEAD,
+            ALIVE if self.next_state(neighbour, true) => ALIVE,
+            cell => self.decay(cell),
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::CONWAY
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Writes single digits when every count is below 10, and the comma
+    /// separated form accepted by `Rule::parse` otherwise. A lone count
+    /// such as `10` gets a trailing comma, or it would read back as the
+    /// digits 1 and 0.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = |table: &NeighbourTable| -> String {
+            if table[10..].iter().all(|set| !set) {
+                return (0..10).filter(|count| table[*count]).map(|count| count.to_string()).collect();
+            }
+            let mut runs: Vec<String> = Vec::new();
+            let mut count = 0;
+            while count < table.len() {
+                if !table[count] {
+                    count += 1;
+                    continue;
+                }
+                let start = count;
+                while count + 1 < table.len() && table[count + 1] {
+                    count += 1;
+                }
+                runs.push(if start == count { start.to_string() } else { format!("{start}-{count}") });
+                count += 1;
+            }
+            let mut list = runs.join(",");
+            if !list.contains([',', '-']) {
+                list.push(',');
+            }
+            list
+        };
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+const fn neighbour_table(counts: &[usize]) -> NeighbourTable {
+    let mut table = [false; Rule::MAX_COUNT + 1];
+    let mut i = 0;
+    while i < counts.len() {
+        table[counts[i]] = true;
+        i += 1;
+    }
+    table
+}
+
+fn parse_neighbour_count(count: &str) -> Result<usize, String> {
+    match count.trim().parse() {
+        Ok(count) if count <= Rule::MAX_COUNT => Ok(count),
+        _ => Err(format!("'{count}' is not a neighbour count (0-{})", Rule::MAX_COUNT)),
+    }
+}
+
+/// Where each index from `-range` to `length + range` lands on an axis of
+/// `length` cells, `None` past a dead edge.
+fn wrapped_indices(length: usize, range: i32, boundary: Boundary) -> Vec<Option<usize>> {
+    (-range..length as i32 + range)
+        .map(|i| match boundary {
+            Boundary::Toroidal => Some(i.rem_euclid(length as i32) as usize),
+            Boundary::Dead => (0..length as i32).contains(&i).then_some(i as usize),
+        })
+        .collect()
+}
+
+/// Random soup where each cell is alive with probability `density`: the
+/// same seed, size and density always give the same board.
+fn initialize_game_table(size: (usize, usize), seed: u64, density: f64) -> GameTable {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut game_table: GameTable = Vec::new();
+    for _ in 0..size.0 {
+        let mut row: Vec<Cell> = Vec::new();
+        for _ in 0..size.1 {
+            let rng_bool: bool = rng.random_bool(density);
+            row.push(if rng_bool { ALIVE } else { DEAD });
+        }
+        game_table.push(row);
+    }
+
+    game_table
+}
+
+fn initialize_empty_game_table(size: (usize, usize)) -> GameTable {
+    let mut game_table: GameTable = Vec::new();
+    for _ in 0..size.0 {
+        game_table.push(vec![DEAD; size.1]);
+    }
+
+    game_table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(rows: &[&str]) -> Board {
+        Board::from_cells(rows.iter().map(|row| row.chars().map(|c| if c == 'O' { ALIVE } else { DEAD }).collect()).collect())
+    }
+
+    #[test]
+    fn blinker_oscillates_with_period_two() {
+        let vertical = board(&[".....", "..O..", "..O..", "..O..", "....."]);
+        let horizontal = board(&[".....", ".....", ".OOO.", ".....", "....."]);
+
+        let next = vertical.step(&Rule::CONWAY, Boundary::Toroidal);
+        assert_eq!(next, horizontal);
+        assert_eq!(next.step(&Rule::CONWAY, Boundary::Toroidal), vertical);
+    }
+
+    #[test]
+    fn neighbour_counts_match_count_number_of_neighbour() {
+        let board = Board::random((13, 17), 5, 0.4);
+        let walls: Vec<Vec<bool>> = Board::random(board.size, 6, 0.1)
+            .cells
+            .iter()
+            .map(|row| row.iter().map(|cell| *cell == ALIVE).collect())
+            .collect();
+
+        let mut counts = Vec::new();
+        for boundary in [Boundary::Toroidal, Boundary::Dead] {
+            for range in 1..=3 {
+                board.neighbour_counts(&mut counts, range, boundary, Some(&walls));
+                for (x, row) in counts.iter().enumerate() {
+                    for (y, count) in row.iter().enumerate() {
+                        let expected = board.count_number_of_neighbour(x, y, range, boundary, Some(&walls));
+                        assert_eq!(*count, expected, "({x}, {y}) at range {range}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn block_is_stable() {
+        let block = board(&["....", ".OO.", ".OO.", "...."]);
+
+        for boundary in [Boundary::Toroidal, Boundary::Dead] {
+            assert_eq!(block.step(&Rule::CONWAY, boundary), block);
+        }
+    }
+
+    #[test]
+    fn rules_past_nine_read_back_the_same() {
+        for rulestring in ["B3/S29", "B10,/S2", "B3,10-12/S2", "B34-45/S34-58/C4"] {
+            let rule = Rule::parse(rulestring).unwrap();
+            let displayed = rule.to_string();
+            assert!(Rule::parse(&displayed).unwrap() == rule, "{rulestring} displayed as {displayed}");
+        }
+        assert_eq!(Rule::parse("B3/S29").unwrap().to_string(), "B3/S29");
+        assert!(Rule::parse("B10,/S").unwrap().next_state(10, false));
+    }
+}
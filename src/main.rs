@@ -1,34 +1,362 @@
-use crossterm::event::{self, poll, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{
+    self, poll, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+    MouseButton, MouseEvent, MouseEventKind,
+};
+use crossterm::execute;
 use ratatui::buffer::Buffer;
-use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::layout::{Constraint, Flex, Layout, Position, Rect};
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::{Row, Widget};
+use ratatui::widgets::{Block, Clear, Sparkline, Widget};
 use ratatui::widgets::Paragraph;
 use ratatui::DefaultTerminal;
 use ratatui::Frame;
 use std::io;
+use std::collections::VecDeque;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::rc::Rc;
 
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use ratatui::style::{Color, Style, Stylize};
 use std::time::{Duration, Instant};
 use ratatui::prelude::Direction;
+use patterns::{Pattern, GLIDER, GOSPER_GLIDER_GUN, PATTERNS};
+use life::{Board, Boundary, Cell, GameTable, Rule, ALIVE, DEAD, MAX_RANGE};
+use session::Session;
 
-type GameTable = Vec<Vec<bool>>;
+mod cli;
+mod formats;
+mod life;
+mod patterns;
+mod session;
+
+/// Order in which cells are updated each generation.
+///
+/// `Simultaneous` is standard Life: every cell is computed from the previous
+/// generation. The other orders update the grid in place, one cell at a
+/// time, so later cells see the already-updated state of earlier ones.
+/// `Random` shuffles the cells anew each generation with `App::rng`, so a
+/// run is reproducible from its seed.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum UpdateOrder {
+    #[default]
+    Simultaneous,
+    RowMajor,
+    ColumnMajor,
+    Random,
+}
+
+impl UpdateOrder {
+    fn name(self) -> &'static str {
+        match self {
+            UpdateOrder::Simultaneous => "simultaneous",
+            UpdateOrder::RowMajor => "row-major",
+            UpdateOrder::ColumnMajor => "column-major",
+            UpdateOrder::Random => "random",
+        }
+    }
+}
+
+/// Mirroring applied to cells toggled with `<s>`, relative to the grid
+/// center. `Horizontal` mirrors left to right, `Vertical` top to bottom and
+/// `Both` does both, giving four cells. `FourFold` adds the quarter-turn
+/// rotations instead, dropping those that fall outside a non-square grid.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Symmetry {
+    #[default]
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+    FourFold,
+}
+
+impl Symmetry {
+    fn name(self) -> &'static str {
+        match self {
+            Symmetry::None => "none",
+            Symmetry::Horizontal => "horizontal",
+            Symmetry::Vertical => "vertical",
+            Symmetry::Both => "both",
+            Symmetry::FourFold => "4-fold",
+        }
+    }
+
+    /// `(x, y)` and its images in a grid of `size`, without repeats.
+    fn images(self, (x, y): (usize, usize), (height, width): (usize, usize)) -> Vec<(usize, usize)> {
+        let mirrored = (height - 1 - x, width - 1 - y);
+        let mut images = match self {
+            Symmetry::None => vec![(x, y)],
+            Symmetry::Horizontal => vec![(x, y), (x, mirrored.1)],
+            Symmetry::Vertical => vec![(x, y), (mirrored.0, y)],
+            Symmetry::Both => vec![(x, y), (x, mirrored.1), (mirrored.0, y), mirrored],
+            Symmetry::FourFold => {
+                // Doubled offsets from the center keep the half cells of
+                // even sides in integers.
+                let (dx, dy) = (2 * x as i64 - (height as i64 - 1), 2 * y as i64 - (width as i64 - 1));
+                [(dx, dy), (dy, -dx), (-dx, -dy), (-dy, dx)]
+                    .into_iter()
+                    .filter_map(|(dx, dy)| {
+                        let (x, y) = (dx + height as i64 - 1, dy + width as i64 - 1);
+                        let inside = x >= 0 && y >= 0 && x % 2 == 0 && y % 2 == 0;
+                        let (x, y) = ((x / 2) as usize, (y / 2) as usize);
+                        (inside && x < height && y < width).then_some((x, y))
+                    })
+                    .collect()
+            }
+        };
+        images.sort_unstable();
+        images.dedup();
+        images
+    }
+}
+
+/// Rotation and mirroring applied to a library pattern before it is
+/// stamped: `flipped` mirrors it left to right, then it turns
+/// `quarter_turns` times clockwise.
+#[derive(Clone, Copy, Default, PartialEq)]
+struct Orientation {
+    quarter_turns: u8,
+    flipped: bool,
+}
+
+impl Orientation {
+    /// Orientations of the library glider heading south-east, south-west,
+    /// north-west and north-east, as listed in the glider menu.
+    const GLIDER_DIRECTIONS: [(&'static str, Orientation); 4] = [
+        ("South-east", Orientation { quarter_turns: 0, flipped: false }),
+        ("South-west", Orientation { quarter_turns: 1, flipped: false }),
+        ("North-west", Orientation { quarter_turns: 2, flipped: false }),
+        ("North-east", Orientation { quarter_turns: 3, flipped: false }),
+    ];
+
+    fn turned(self, quarter_turns: u8) -> Orientation {
+        Orientation { quarter_turns: (self.quarter_turns + quarter_turns) % 4, ..self }
+    }
+
+    fn apply<R: AsRef<[Cell]>>(self, cells: &[R]) -> GameTable {
+        let mut table: GameTable = cells.iter().map(|row| row.as_ref().to_vec()).collect();
+        if self.flipped {
+            table.iter_mut().for_each(|row| row.reverse());
+        }
+        for _ in 0..self.quarter_turns {
+            table = rotate_table(&table);
+        }
+        table
+    }
+
+    fn name(self) -> String {
+        let name = format!("{}°", u16::from(self.quarter_turns) * 90);
+        if self.flipped { format!("{name}, flipped") } else { name }
+    }
+}
+
+/// Text being typed in the prompt shown in place of the information line.
+struct Prompt {
+    kind: PromptKind,
+    input: String,
+}
+
+#[derive(Clone, Copy)]
+enum PromptKind {
+    Rule,
+    AliveColor,
+}
+
+impl PromptKind {
+    fn label(self) -> &'static str {
+        match self {
+            PromptKind::Rule => "Rule (B/S notation)",
+            PromptKind::AliveColor => "Alive color (#RRGGBB)",
+        }
+    }
+}
+
+/// Colors of the board and status lines, cycled with `<M>`.
+#[derive(Clone, Copy, PartialEq)]
+struct Theme {
+    name: &'static str,
+    alive: Color,
+    cursor: Color,
+    /// Brush square around the cursor.
+    brush: Color,
+    /// Keys and values of the status lines.
+    accent: Color,
+}
+
+impl Theme {
+    const PRESETS: [Theme; 3] = [
+        Theme {
+            name: "default",
+            alive: Color::Reset,
+            cursor: Color::LightGreen,
+            brush: Color::Green,
+            accent: Color::Blue,
+        },
+        // High contrast for light terminal backgrounds.
+        Theme {
+            name: "light",
+            alive: Color::Black,
+            cursor: Color::Magenta,
+            brush: Color::LightMagenta,
+            accent: Color::Indexed(18),
+        },
+        Theme {
+            name: "amber",
+            alive: Color::Yellow,
+            cursor: Color::LightRed,
+            brush: Color::Red,
+            accent: Color::Yellow,
+        },
+    ];
+}
+
+/// Weighted neighbourhood used instead of the plain Moore count.
+///
+/// The weighted sum covers every cell of the matrix, center included:
+/// give the center a weight of 0 to leave the cell itself out, as Life does.
+/// A cell is born when the sum falls in `birth` and stays alive when it
+/// falls in `survive` (both inclusive).
+struct Kernel {
+    weights: Vec<Vec<i32>>,
+    birth: (i32, i32),
+    survive: (i32, i32),
+}
+
+impl Kernel {
+    const FILE: &'static str = "kernel.txt";
+
+    /// Parses a kernel file: rows of whitespace-separated weights, followed
+    /// by `birth <min> <max>` and `survive <min> <max>` lines.
+    fn parse(input: &str) -> io::Result<Kernel> {
+        let mut weights = Vec::new();
+        let mut birth = None;
+        let mut survive = None;
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("birth") => birth = Some(parse_range(words)?),
+                Some("survive") => survive = Some(parse_range(words)?),
+                _ => {
+                    let row = line
+                        .split_whitespace()
+                        .map(|weight| weight.parse::<i32>().map_err(|_| invalid_kernel(&format!("bad weight '{weight}'"))))
+                        .collect::<io::Result<Vec<i32>>>()?;
+                    weights.push(row);
+                }
+            }
+        }
+
+        let height = weights.len();
+        let width = weights.first().map_or(0, Vec::len);
+        if height % 2 == 0 || width % 2 == 0 {
+            return Err(invalid_kernel("dimensions must be odd"));
+        }
+        if weights.iter().any(|row| row.len() != width) {
+            return Err(invalid_kernel("rows must all have the same length"));
+        }
+
+        Ok(Kernel {
+            weights,
+            birth: birth.ok_or_else(|| invalid_kernel("missing birth line"))?,
+            survive: survive.ok_or_else(|| invalid_kernel("missing survive line"))?,
+        })
+    }
+
+    fn next_state(&self, sum: i32, alive: bool) -> bool {
+        let (min, max) = if alive { self.survive } else { self.birth };
+        sum >= min && sum <= max
+    }
+}
+
+fn parse_range<'a>(mut words: impl Iterator<Item = &'a str>) -> io::Result<(i32, i32)> {
+    let mut bound = || {
+        words
+            .next()
+            .and_then(|word| word.parse::<i32>().ok())
+            .ok_or_else(|| invalid_kernel("range needs <min> <max>"))
+    };
+    Ok((bound()?, bound()?))
+}
+
+fn invalid_kernel(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid kernel: {reason}"))
+}
 
 fn main() -> io::Result<()> {
+    let args = match cli::Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    let mut app = App::default();
+    if let Some(seed) = args.seed {
+        app.seed = seed;
+    }
+    if let Some(update_per_second) = args.update_per_second {
+        app.update_per_second_max = update_per_second;
+    }
+    if let Some(png_scale) = args.png_scale {
+        app.png_scale = png_scale;
+    }
+    app.autosave_interval = args.autosave_interval;
+    app.stop_at = args.generations;
+    app.game_pause = args.paused;
+    app.confirm_regenerate = args.confirm_new_soup;
+    app.requested_grid_size = (args.height, args.width);
+    if args.resume {
+        match formats::read_pattern_file(Path::new(App::AUTOSAVE_FILE)) {
+            Ok(board) => {
+                let size = (board.len(), board.first().map_or(0, Vec::len));
+                app.requested_grid_size = (args.height.or(Some(size.0)), args.width.or(Some(size.1)));
+                app.initial_pattern = Some(board);
+            }
+            Err(error) => eprintln!("warning: could not resume from {}: {error}", App::AUTOSAVE_FILE),
+        }
+    } else if let Some(path) = &args.pattern {
+        match formats::read_pattern_file(path) {
+            Ok(pattern) => app.initial_pattern = Some(pattern),
+            Err(error) => eprintln!("warning: could not load {}: {error}, starting from a random board", path.display()),
+        }
+    }
+
+    if let Some(generations) = args.bench {
+        println!("{}", app.bench(generations));
+        return Ok(());
+    }
+
     let mut terminal = ratatui::init();
-    let app_result = App::default().run(&mut terminal);
+    execute!(io::stdout(), EnableMouseCapture)?;
+    let app_result = app.run(&mut terminal);
+    execute!(io::stdout(), DisableMouseCapture)?;
     ratatui::restore();
-    app_result
+    app_result?;
+
+    // Only now that the terminal is restored, so the board ends up on the
+    // normal screen or in a pipe rather than in the TUI.
+    if args.dump_on_exit {
+        print!("{}", formats::plaintext_rows(&app.board.cells, '#', '.'));
+    }
+    Ok(())
 }
 
-#[derive(Default)]
 struct App {
     exit: bool,
-    game_table: GameTable,
-    game_table_size: (usize, usize),
+    board: Board,
+    back_board: Board,
     time_to_update: Duration,
-    update_per_second_max: u16,
+    /// The last `UPDATE_TIMES_WINDOW` values of `time_to_update`.
+    update_times: VecDeque<Duration>,
+    update_per_second_max: f64,
     update_par_second_real: u16,
     time_to_draw: Duration,
     game_pause: bool,
@@ -36,31 +364,450 @@ struct App {
     game_table_user_cursor2: (usize, usize),
     fps: u32,
     step_by_step_next: bool,
+    /// Generations the pending `<t>` advances.
+    step_by_step_count: u64,
+    /// Count typed before `<t>` while paused, vi style.
+    count_prefix: Option<u64>,
+    terminal_size: (usize, usize),
+    square_world: bool,
+    kernel: Option<Kernel>,
+    notice: Option<String>,
+    ever_changed: Vec<Vec<bool>>,
+    show_never_changed: bool,
+    /// Cells that went from alive to dead in the last update.
+    just_died: Vec<Vec<bool>>,
+    /// Draws `just_died` cells dimmed for a generation instead of blank.
+    smooth_death: bool,
+    update_order: UpdateOrder,
+    nudge_offset: (isize, isize),
+    birth_prob: f64,
+    survive_prob: f64,
+    rng: StdRng,
+    pattern_menu: Option<usize>,
+    /// Selected direction while the glider heading menu is open.
+    glider_menu: Option<usize>,
+    pattern_orientation: Orientation,
+    boundary: Boundary,
+    rule: Rule,
+    prompt: Option<Prompt>,
+    generation: u64,
+    viewport_origin: (usize, usize),
+    brush_size: usize,
+    edit_mode: bool,
+    selection_start: Option<(usize, usize)>,
+    clipboard: Option<GameTable>,
+    /// Boards before each recent step or edit, with their generation.
+    history: VecDeque<(GameTable, u64)>,
+    redo_history: Vec<(GameTable, u64)>,
+    seed: u64,
+    fill_density: f64,
+    /// Generations each live cell has survived, 0 for newborn and dead cells.
+    cell_age: Vec<Vec<u16>>,
+    show_heatmap: bool,
+    alive_char: char,
+    dead_char: char,
+    show_grid: bool,
+    grid_spacing: usize,
+    fast_forward_count: u64,
+    /// How long the last `<j>` batch took, shown apart from the per-update
+    /// percentiles it would skew.
+    fast_forward_time: Option<Duration>,
+    /// Hashes of the most recent boards, oldest first.
+    board_hashes: VecDeque<u64>,
+    detected_period: Option<u64>,
+    /// First generation of the cycle the board settled into, once a period
+    /// is detected.
+    stabilized_at: Option<u64>,
+    /// Highest population reached by a step since the last edit, with its
+    /// generation.
+    peak_population: Option<(usize, u64)>,
+    pause_on_period: bool,
+    /// Population after each recent generation, one per sparkline column.
+    population_history: VecDeque<u64>,
+    /// `(height, width)` asked for on the command line, or last set with
+    /// `<+>`/`<->`. Layout changes keep it instead of fitting the terminal.
+    requested_grid_size: (Option<usize>, Option<usize>),
+    /// Pattern given on the command line, placed instead of the random soup.
+    initial_pattern: Option<GameTable>,
+    show_help: bool,
+    /// Immortal cells: never updated, and always counted as alive
+    /// neighbours. Kept apart from `board` so resets leave them.
+    walls: Vec<Vec<bool>>,
+    /// Set by a full `<n>` reset while walls remain: a second `<n>` right
+    /// after it clears them too.
+    clear_walls_armed: bool,
+    /// Neighbourhood reach: cells up to `range` rows and columns away are
+    /// counted, 1 being the classic Moore neighbourhood.
+    range: i32,
+    /// Scratch grid for `Board::neighbour_counts`, kept between steps.
+    neighbour_counts: Vec<Vec<u8>>,
+    aspect_correct: bool,
+    show_status: bool,
+    /// In-memory boards saved with `<Alt-N>`, restored with `<'>` `<N>`.
+    slots: [Option<GameTable>; App::SLOT_COUNT],
+    /// Set by `<'>`: the next digit restores that slot.
+    slot_restore_armed: bool,
+    /// Compute the rows of each generation on all cores. Needs the
+    /// `parallel` feature.
+    parallel: bool,
+    /// Cells that changed in the last update, as `track_cell` saw them.
+    changed_cells: Vec<(usize, usize)>,
+    /// Cells the next update recomputes, with `dirty` as their lookup grid.
+    dirty_cells: Vec<(usize, usize)>,
+    dirty: Vec<Vec<bool>>,
+    /// Forces the next update to recompute every cell. Set by anything that
+    /// changes the board outside of `step`.
+    all_dirty: bool,
+    /// Rule, boundary and range `dirty_cells` were collected under.
+    dirty_signature: Option<(Rule, Boundary, i32)>,
+    theme: Theme,
+    /// Pixels per cell side in exported images.
+    png_scale: u32,
+    /// Boards of the generations recorded so far, while recording a GIF.
+    recording: Option<Vec<GameTable>>,
+    /// How often `run` writes the board to `AUTOSAVE_FILE`, if at all.
+    autosave_interval: Option<Duration>,
+    /// Last grid cell of the mouse stroke being drawn, while the left
+    /// button is held.
+    mouse_stroke: Option<(usize, usize)>,
+    /// Generation to pause at, from `--generations`.
+    stop_at: Option<u64>,
+    symmetry: Symmetry,
+    /// Shows live cells per visible column in place of the sparkline.
+    show_column_histogram: bool,
+    /// Tints the outermost rows and columns: blue where the torus wraps,
+    /// red where a dead boundary walls the board off.
+    show_edges: bool,
+    /// When a first `<n>` asked to confirm clearing the whole board.
+    reset_armed: Option<Instant>,
+    /// Same for `<N>`, which only asks with `confirm_regenerate` set.
+    regenerate_armed: Option<Instant>,
+    confirm_regenerate: bool,
+    /// Last `<U>` press of the speed ramp, kept while the rate eases back
+    /// to the default after the key is released.
+    ramp_pressed_at: Option<Instant>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App {
+            exit: false,
+            board: Board::default(),
+            back_board: Board::default(),
+            time_to_update: Duration::ZERO,
+            update_times: VecDeque::new(),
+            update_per_second_max: App::DEFAULT_MAX_UPDATE_PER_SECOND,
+            update_par_second_real: 0,
+            time_to_draw: Duration::ZERO,
+            game_pause: false,
+            game_table_user_cursor: (0, 0),
+            game_table_user_cursor2: (0, 0),
+            fps: 0,
+            step_by_step_next: false,
+            step_by_step_count: 1,
+            count_prefix: None,
+            terminal_size: (0, 0),
+            square_world: false,
+            kernel: None,
+            notice: None,
+            ever_changed: Vec::new(),
+            show_never_changed: false,
+            just_died: Vec::new(),
+            smooth_death: false,
+            update_order: UpdateOrder::default(),
+            nudge_offset: (0, 0),
+            birth_prob: 1.0,
+            survive_prob: 1.0,
+            rng: StdRng::seed_from_u64(0),
+            pattern_menu: None,
+            glider_menu: None,
+            pattern_orientation: Orientation::default(),
+            boundary: Boundary::default(),
+            rule: Rule::default(),
+            prompt: None,
+            generation: 0,
+            viewport_origin: (0, 0),
+            brush_size: 1,
+            edit_mode: false,
+            selection_start: None,
+            clipboard: None,
+            history: VecDeque::new(),
+            redo_history: Vec::new(),
+            seed: rand::rng().random(),
+            fill_density: 0.5,
+            cell_age: Vec::new(),
+            show_heatmap: false,
+            alive_char: App::GLYPH_PRESETS[0].0,
+            dead_char: App::GLYPH_PRESETS[0].1,
+            show_grid: false,
+            grid_spacing: App::GRID_SPACINGS[1],
+            fast_forward_count: 100,
+            fast_forward_time: None,
+            board_hashes: VecDeque::new(),
+            detected_period: None,
+            stabilized_at: None,
+            peak_population: None,
+            pause_on_period: false,
+            population_history: VecDeque::new(),
+            requested_grid_size: (None, None),
+            initial_pattern: None,
+            show_help: false,
+            walls: Vec::new(),
+            clear_walls_armed: false,
+            range: 1,
+            neighbour_counts: Vec::new(),
+            aspect_correct: false,
+            show_status: true,
+            slots: Default::default(),
+            slot_restore_armed: false,
+            parallel: false,
+            changed_cells: Vec::new(),
+            dirty_cells: Vec::new(),
+            dirty: Vec::new(),
+            all_dirty: true,
+            dirty_signature: None,
+            theme: Theme::PRESETS[0],
+            png_scale: App::DEFAULT_PNG_SCALE,
+            recording: None,
+            autosave_interval: None,
+            mouse_stroke: None,
+            stop_at: None,
+            symmetry: Symmetry::None,
+            show_column_histogram: false,
+            show_edges: false,
+            reset_armed: None,
+            regenerate_armed: None,
+            confirm_regenerate: false,
+            ramp_pressed_at: None,
+        }
+    }
 }
+
 impl App {
-    const DEFAULT_MAX_UPDATE_PER_SECOND: u16 = 10;
+    const DEFAULT_MAX_UPDATE_PER_SECOND: f64 = 10.0;
+    /// Halving stops here, at one update every four seconds.
+    const MIN_UPDATE_PER_SECOND: f64 = 0.25;
+    const MAX_UPDATE_PER_SECOND: f64 = 10_000.0;
+    /// Factor per second by which the speed ramp raises the rate while
+    /// `<U>` is held, and lowers it back once released.
+    const RAMP_FACTOR_PER_SECOND: f64 = 2.0;
+    const RAMP_MAX_UPDATE_PER_SECOND: f64 = 1_000.0;
+    /// `<U>` counts as held for this long after each press, which covers
+    /// the delay before the terminal starts repeating the key.
+    const RAMP_HOLD: Duration = Duration::from_millis(600);
+    /// Updates kept for the timing percentiles of the information line.
+    const UPDATE_TIMES_WINDOW: usize = 120;
+    /// Rate change of `<Ctrl-a>`/`<Ctrl-d>`.
+    const UPDATE_PER_SECOND_LARGE_STEP: f64 = 10.0;
+    const PROBABILITY_STEP: f64 = 0.05;
+    const RLE_FILE: &'static str = "save.rle";
+    const CELLS_FILE: &'static str = "pattern.cells";
+    const AUTOSAVE_FILE: &'static str = "autosave.rle";
+    const SESSION_FILE: &'static str = "session.json";
+    /// Written when the board can't be put on the system clipboard.
+    const CLIPBOARD_FALLBACK_FILE: &'static str = "clipboard.cells";
+    const DEFAULT_PNG_SCALE: u32 = 4;
+    const MAX_PNG_SCALE: u32 = 16;
+    /// The dirty-cell update is used while at most one cell in this many
+    /// needs recomputing.
+    const DIRTY_CELLS_FRACTION: usize = 8;
+    /// `(height, width)` of the `--bench` grid when no size is given.
+    const BENCH_GRID_SIZE: (usize, usize) = (200, 200);
+    /// Pixels per cell side in recorded GIFs, kept small as every frame is
+    /// stored until the recording stops.
+    #[cfg(feature = "image")]
+    const GIF_SCALE: u32 = 2;
+    const MAX_RECORDED_FRAMES: usize = 500;
+    /// Rows taken by the instruction line, information line and sparkline.
+    const STATUS_ROWS: usize = 3;
+    const MIN_GRID_SIZE: usize = 3;
+    /// Largest grid side: at two columns per cell the board still fits
+    /// the `u16` widths ratatui lays out.
+    const MAX_GRID_SIZE: usize = 4096;
+    const GRID_RESIZE_STEP: usize = 10;
+    const MAX_BRUSH_SIZE: usize = 15;
+    const HISTORY_LENGTH: usize = 50;
+    const PERIOD_DETECTION_DEPTH: usize = 16;
+    const SLOT_COUNT: usize = 9;
+    /// Top-left corner of the gun in the `<Ctrl-p>` demo.
+    const DEMO_OFFSET: (usize, usize) = (2, 2);
+    const DEMO_UPDATE_PER_SECOND: f64 = 20.0;
+    /// Frame length while paused, for about 30 fps.
+    const IDLE_FRAME_TIME: Duration = Duration::from_millis(33);
+    /// Largest count accepted before `<t>`.
+    const MAX_STEP_COUNT: u64 = 10_000;
+    /// Time to press a destructive key again to confirm it.
+    const CONFIRM_WINDOW: Duration = Duration::from_secs(3);
+    /// Cells kept between the cursor and the viewport edge while it moves.
+    const VIEWPORT_MARGIN: usize = 3;
+    /// `(alive, dead)` glyph pairs cycled with `<G>`.
+    const GLYPH_PRESETS: [(char, char); 3] = [('#', ' '), ('█', '·'), ('O', '.')];
+    const GRID_SPACINGS: [usize; 3] = [5, 10, 20];
+    /// Update rates of the `<1>`..`<9>` keys.
+    const SPEED_PRESETS: [f64; 9] = [1.0, 2.0, 5.0, 10.0, 30.0, 45.0, 60.0, 90.0, 120.0];
+    /// Every key binding, listed by the help overlay.
+    const KEY_BINDINGS: &'static [(&'static str, &'static str)] = &[
+        ("<q>", "quit"),
+        ("<Space>", "pause"),
+        ("<Arrow>", "move cursor while paused"),
+        ("<Shift-Arrow>", "move selection corner"),
+        ("<Home>/<End>", "cursor to first/last column"),
+        ("<PgUp>/<PgDn>", "cursor to top/bottom row"),
+        ("<C>", "center cursor"),
+        ("<!>", "invert board"),
+        ("<Alt-1..9>", "save to slot"),
+        ("<'><1..9>", "restore slot"),
+        ("<P>", "parallel update"),
+        ("<s>", "paint brush"),
+        ("<|>", "drawing symmetry"),
+        ("<a>", "decrease update rate"),
+        ("<d>", "increase update rate"),
+        ("<Ctrl-a>/<Ctrl-d>", "update rate by 10"),
+        ("</>/<*>", "halve/double update rate"),
+        ("<r>", "reset update rate"),
+        ("<U> (hold)", "ramp update rate up, eases back on release"),
+        ("<1..9>", "update rate preset, step count when paused"),
+        ("<t>", "step by step, or as many as typed before"),
+        ("<T>", "step back one generation"),
+        ("<n>", "clear board (press twice), then walls"),
+        ("<e>", "export selected"),
+        ("<Ctrl-e>", "copy board as text to system clipboard"),
+        ("<i>", "import data"),
+        ("<x>", "square world"),
+        ("<k>/<K>", "load/clear kernel"),
+        ("<v>", "never changed"),
+        ("<J>", "dim cells for a generation after they die"),
+        ("<o>", "update order"),
+        ("<Ctrl-Arrow>", "nudge pattern"),
+        ("<[>/<]>", "birth chance"),
+        ("<{>/<}>", "survive chance"),
+        ("<w>/<l>", "save/load RLE"),
+        ("<L>", "load .cells at cursor"),
+        ("<Ctrl-s>/<Ctrl-l>", "save/load session"),
+        ("<E>", "export PNG image"),
+        ("<D>", "start/stop GIF recording"),
+        ("<p>", "pattern library"),
+        ("<Ctrl-p>", "glider gun demo"),
+        ("<b>", "boundary"),
+        ("<B>", "highlight board edges"),
+        ("<R>", "rule"),
+        ("<H>", "alive color as #RRGGBB"),
+        ("<+>/<->", "grow/shrink grid"),
+        ("<Alt-Arrow>", "pan"),
+        ("<Ctrl-f>", "center the view on the live cells"),
+        ("<Click>", "toggle cell"),
+        ("<<>/<>>", "brush size"),
+        ("<m>", "edit while running"),
+        ("<z>", "set/drop anchor"),
+        ("<f>/<F>", "fill/clear to anchor"),
+        ("<Z>", "random soup to anchor, or whole board"),
+        ("<c>", "copy to anchor"),
+        ("<y>/<Y>", "paste/paste over"),
+        ("<O>", "rotate clipboard clockwise"),
+        ("<X>/<V>", "flip clipboard left-right/top-bottom"),
+        ("<u>/<Ctrl-r>", "undo/redo"),
+        ("<N>", "new soup"),
+        ("<(>/<)>", "soup density"),
+        ("<h>", "age heatmap"),
+        ("<G>", "glyphs"),
+        ("<M>", "color theme"),
+        ("<g>/<Ctrl-g>", "grid lines/spacing"),
+        ("<j>", "fast-forward"),
+        ("<S>", "pause when periodic"),
+        ("<W>", "paint/erase walls"),
+        ("<,>/<.>", "neighbourhood range"),
+        ("<A>", "square cells (two columns each)"),
+        ("<I>", "show/hide information line"),
+        ("<#>", "column histogram instead of sparkline"),
+        ("<?>", "show/hide this help"),
+    ];
+    /// Seeds a random board of `size`, grown to `MIN_GRID_SIZE` on a tiny
+    /// terminal, with the pattern given on the command line placed on it
+    /// if there is one.
+    fn start_board(&mut self, size: (usize, usize)) {
+        let size = (size.0.max(App::MIN_GRID_SIZE), size.1.max(App::MIN_GRID_SIZE));
+        self.board = Board::random(size, self.seed, self.fill_density);
+        self.walls = vec![vec![false; self.board.size.1]; self.board.size.0];
+        self.rng = StdRng::seed_from_u64(self.seed);
+        self.reset_board_tracking();
+        if let Some(pattern) = self.initial_pattern.take() {
+            self.place_loaded_pattern(&pattern);
+            self.clear_history();
+        }
+    }
+
+    /// Runs `generations` updates without a terminal and reports the
+    /// throughput. The grid is `requested_grid_size`, or
+    /// `BENCH_GRID_SIZE` where it isn't given.
+    fn bench(&mut self, generations: u64) -> String {
+        let size = (
+            self.requested_grid_size.0.unwrap_or(App::BENCH_GRID_SIZE.0),
+            self.requested_grid_size.1.unwrap_or(App::BENCH_GRID_SIZE.1),
+        );
+        self.start_board(size);
+
+        let mut total = Duration::ZERO;
+        for _ in 0..generations {
+            let time_to_update_t1 = Instant::now();
+            self.update_game_table();
+            self.time_to_update = time_to_update_t1.elapsed();
+            total += self.time_to_update;
+        }
+
+        let average = total / generations.max(1) as u32;
+        format!(
+            "{generations} generations on {}x{} (seed {}): {:.1} generations/s, average time_to_update {average:?}",
+            size.1,
+            size.0,
+            self.seed,
+            generations as f64 / total.as_secs_f64(),
+        )
+    }
+
     fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
         let terminal_size = terminal.size()?;
-        self.update_per_second_max = App::DEFAULT_MAX_UPDATE_PER_SECOND;
-        self.game_table_size = (terminal_size.height as usize, terminal_size.width as usize);
-        self.game_table = initialize_game_table(self.game_table_size);
+        self.terminal_size = (terminal_size.height as usize, terminal_size.width as usize);
+        self.start_board(self.world_size());
+        if self.game_pause {
+            // Starting paused is for editing first, so put the cursor where
+            // the drawing usually begins.
+            self.center_cursor();
+        }
         let mut last_fps_update = Instant::now();
         let mut last_update = Instant::now();
+        let mut last_autosave = Instant::now();
         let mut frame_count = 0;
         let mut update_per_second_count = 0;
+        let mut last_frame_start = Instant::now();
         while !self.exit {
-            if !self.game_pause {
-                if Instant::now() - last_update >= Duration::from_secs_f64(1.0 / self.update_per_second_max as f64) {
+            let frame_start = Instant::now();
+            self.ramp_update_per_second_max(frame_start, frame_start - last_frame_start);
+            last_frame_start = frame_start;
+            // A terminal too small for the board holds the game without
+            // touching `game_pause`, so it resumes once enlarged.
+            let held = self.game_pause || self.terminal_too_small();
+            if !held {
+                if Instant::now() - last_update >= Duration::from_secs_f64(1.0 / self.update_per_second_max) {
                     let time_to_update_t1 = Instant::now();
-                    self.game_table = self.update_game_table(self.game_table.clone());
-                    self.time_to_update = time_to_update_t1.elapsed();
+                    self.step();
+                    self.record_update_time(time_to_update_t1.elapsed());
                     last_update = Instant::now();
                     update_per_second_count += 1;
+                    self.check_stop_at();
                 }
             } else if self.step_by_step_next {
-                self.game_table = self.update_game_table(self.game_table.clone());
+                for _ in 0..std::mem::replace(&mut self.step_by_step_count, 1) {
+                    self.step();
+                    if self.population() == 0 || self.check_stop_at() {
+                        break;
+                    }
+                }
                 self.step_by_step_next = false;
             }
+            if self.autosave_interval.is_some_and(|interval| last_autosave.elapsed() >= interval) {
+                self.autosave();
+                last_autosave = Instant::now();
+            }
 
             let time_to_draw_t1 = Instant::now();
             terminal.draw(|frame| self.draw(frame))?;
@@ -78,396 +825,3168 @@ impl App {
                 update_per_second_count = 0;
             }
 
-            self.handle_events()?;
+            // Nothing changes on its own while paused, so wait for input
+            // up to the end of an idle frame instead of spinning.
+            let timeout = if held && !self.step_by_step_next {
+                App::IDLE_FRAME_TIME.saturating_sub(frame_start.elapsed())
+            } else {
+                Duration::ZERO
+            };
+            self.handle_events(timeout)?;
         }
         Ok(())
     }
 
     fn draw(&self, frame: &mut Frame) {
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(1),
-                Constraint::Length(self.game_table_size.0 as u16),
-                Constraint::Length(1),
-            ]).split(frame.area());
+        if self.terminal_too_small() {
+            let [area] = Layout::vertical([Constraint::Length(1)]).flex(Flex::Center).areas(frame.area());
+            frame.render_widget(Paragraph::new("Terminal too small").centered().red(), area);
+            return;
+        }
+        let layout = self.screen_layout(frame.area());
 
         let instructions = Line::from(vec![
             "Quit".into(),
-            " <q>".bold().blue(),
+            " <q>".bold().fg(self.theme.accent),
             ", Pause".into(),
-            " <Space>".bold().blue(),
-            " Move cursor while Pause with".into(),
-            " <Arrow>".bold().blue(),
-            " Switch cell state".into(),
-            " <s>".bold().blue(),
-            " decrease update rate".into(),
-            " <a>".bold().blue(),
-            " increase update rate".into(),
-            " <d>".bold().blue(),
-            " reset update rate".into(),
-            " <r>".bold().blue(),
-            ", step by step".into(),
-            " <t>".bold().blue(),
-            ", reset game".into(),
-            " <n>".bold().blue(),
-            ", export selected".into(),
-            " <e>".bold().blue(),
-            ", import data".into(),
-            " <i>".bold().blue(),
+            " <Space>".bold().fg(self.theme.accent),
+            ", Help".into(),
+            " <?>".bold().fg(self.theme.accent),
+            // The configuration stays in sight even with the information
+            // line hidden or cut off by a narrow terminal.
+            " | rule".into(),
+            format!(" {}", self.rule).fg(self.theme.accent),
+            ", boundary".into(),
+            format!(" {}", self.boundary.name()).fg(self.theme.accent),
+            ", range".into(),
+            format!(" {}", self.range).fg(self.theme.accent),
         ]);
 
-        let information = Line::from(vec![
-            "Time Update [ms]".into(),
-            format!(" {}", self.time_to_update.as_millis()).blue(),
+        let mut information = Line::from(vec![
+            "Generation".into(),
+            format!(" {}", self.generation).fg(self.theme.accent),
+            ", population".into(),
+            format!(" {}", self.population()).fg(self.theme.accent),
+            ", period".into(),
+            match self.detected_period {
+                Some(period) => format!(" {period}").fg(self.theme.accent),
+                None => " -".fg(self.theme.accent),
+            },
+            ", grid".into(),
+            format!(" {}x{}", self.board.size.1, self.board.size.0).fg(self.theme.accent),
+            ", cursor".into(),
+            format!(" ({}, {})", self.game_table_user_cursor.0, self.game_table_user_cursor.1).fg(self.theme.accent),
+            ", view".into(),
+            format!(" {:?}", self.viewport_origin).fg(self.theme.accent),
+            ", brush".into(),
+            format!(" {}", self.brush_size).fg(self.theme.accent),
+            ", seed".into(),
+            format!(" {}", self.seed).fg(self.theme.accent),
+            ", density".into(),
+            format!(" {:.0}%", self.fill_density * 100.0).fg(self.theme.accent),
+            ", grid every".into(),
+            format!(" {}", self.grid_spacing).fg(self.theme.accent),
+            ", symmetry".into(),
+            format!(" {}", self.symmetry.name()).fg(self.theme.accent),
+            ", edit mode".into(),
+            format!(" {}", if self.edit_mode { "on" } else { "off" }).fg(self.theme.accent),
+            ", ".into(),
+            "Time Update min/median/p99 [ms]".into(),
+            match self.update_time_percentiles() {
+                Some((min, median, p99)) => format!(
+                    " {:.1}/{:.1}/{:.1}",
+                    min.as_secs_f64() * 1000.0,
+                    median.as_secs_f64() * 1000.0,
+                    p99.as_secs_f64() * 1000.0
+                )
+                .fg(self.theme.accent),
+                None => " -".fg(self.theme.accent),
+            },
             ", Time Draw [ms]".into(),
-            format!(" {}", self.time_to_draw.as_millis()).blue(),
+            format!(" {}", self.time_to_draw.as_millis()).fg(self.theme.accent),
             ", fps".into(),
-            format!(" {}", self.fps).blue(),
+            format!(" {}", self.fps).fg(self.theme.accent),
             ", max update/[s]".into(),
-            format!(" {}", self.update_per_second_max).blue(),
+            format!(" {:.1}", self.update_per_second_max).fg(self.theme.accent),
             ", real update/[s]".into(),
-            format!(" {}", self.update_par_second_real).blue(),
+            format!(" {}", self.update_par_second_real).fg(self.theme.accent),
+            ", order".into(),
+            format!(" {}", self.update_order.name()).fg(self.theme.accent),
+            ", parallel".into(),
+            format!(" {}", if self.parallel { "on" } else { "off" }).fg(self.theme.accent),
+            ", birth/survive chance".into(),
+            format!(" {:.2}/{:.2}", self.birth_prob, self.survive_prob).fg(self.theme.accent),
         ]);
+        if let Some((peak, generation)) = self.peak_population {
+            information.push_span(", peak");
+            information.push_span(format!(" {peak} at generation {generation}").fg(self.theme.accent));
+        }
+        if let Some(stabilized_at) = self.stabilized_at {
+            information.push_span(", stabilized at generation");
+            information.push_span(format!(" {stabilized_at}").fg(self.theme.accent));
+        }
+        if self.nudge_offset != (0, 0) {
+            information.push_span(", offset");
+            information.push_span(format!(" {:?}", self.nudge_offset).fg(self.theme.accent));
+        }
+        if let Some(fast_forward_time) = self.fast_forward_time {
+            information.push_span(", fast-forward [ms]");
+            information.push_span(format!(" {}", fast_forward_time.as_millis()).fg(self.theme.accent));
+        }
+        if let Some(count) = self.count_prefix {
+            information.push_span(", steps");
+            information.push_span(format!(" {count}<t>").fg(self.theme.accent));
+        }
+        if let Some(frames) = &self.recording {
+            information.push_span(", recording");
+            information.push_span(format!(" {} frames", frames.len()).fg(self.theme.accent));
+        }
+        if let Some(notice) = &self.notice {
+            information.push_span(format!(", {notice}").red());
+        }
+
+        let board_area = self.centered_board_area(layout[1]);
+
+        frame.render_widget(self, board_area);
+        if self.game_pause {
+            let badge = Span::from(" PAUSED ").bold().black().on_yellow();
+            let width = (badge.width() as u16).min(board_area.width);
+            let badge_area = Rect::new(board_area.right() - width, board_area.y, width, 1.min(board_area.height));
+            frame.render_widget(badge, badge_area);
+        }
+        // With the information line hidden, a prompt takes the instruction
+        // line instead.
+        let prompt_area = if self.show_status { layout[2] } else { layout[0] };
+        if self.prompt.is_none() || self.show_status {
+            frame.render_widget(instructions, layout[0]);
+        }
+        match &self.prompt {
+            Some(prompt) => frame.render_widget(
+                Paragraph::new(Line::from(vec![
+                    format!("{}: ", prompt.kind.label()).into(),
+                    format!("{}_", prompt.input).bold().fg(self.theme.accent),
+                    " <Enter> apply, <Esc> cancel".into(),
+                ])),
+                prompt_area,
+            ),
+            None => frame.render_widget(information, layout[2]),
+        }
+
+        if self.show_column_histogram {
+            let histogram_area = Rect { y: layout[3].y, height: layout[3].height, ..board_area };
+            frame.render_widget(self.column_histogram(board_area).fg(self.theme.accent), histogram_area);
+        } else {
+            let population_history: Vec<u64> = self.population_history.iter().copied().collect();
+            frame.render_widget(Sparkline::default().data(&population_history).fg(self.theme.accent), layout[3]);
+        }
+
+        if let Some(selected) = self.pattern_menu {
+            self.draw_pattern_menu(frame, selected);
+        }
+        if let Some(selected) = self.glider_menu {
+            self.draw_glider_menu(frame, selected);
+        }
+        if self.show_help {
+            self.draw_help(frame);
+        }
+    }
+
+    /// Lists every key binding over the whole frame, in as many columns as
+    /// the height requires.
+    fn draw_help(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let block = Block::bordered().title("Help, <?> to close");
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rows = (inner.height as usize).max(1);
+        let columns: Vec<&[(&str, &str)]> = App::KEY_BINDINGS.chunks(rows).collect();
+        let areas = Layout::horizontal(vec![Constraint::Fill(1); columns.len()]).split(inner);
+        for (bindings, area) in columns.iter().zip(areas.iter()) {
+            let key_width = bindings.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+            let lines: Vec<Line> = bindings
+                .iter()
+                .map(|(key, description)| {
+                    Line::from(vec![format!(" {key:>key_width$}").bold().fg(self.theme.accent), format!(" {description}").into()])
+                })
+                .collect();
+            frame.render_widget(Paragraph::new(lines), *area);
+        }
+    }
+
+    fn draw_pattern_menu(&self, frame: &mut Frame, selected: usize) {
+        let names: Vec<&str> = PATTERNS.iter().map(|pattern| pattern.name).collect();
+        let title = format!("Patterns ({}, <Left>/<Right> turn, <f> flip)", self.pattern_orientation.name());
+        self.draw_menu(frame, &title, &names, selected);
+    }
+
+    fn draw_glider_menu(&self, frame: &mut Frame, selected: usize) {
+        let names: Vec<&str> = Orientation::GLIDER_DIRECTIONS.iter().map(|(name, _)| *name).collect();
+        self.draw_menu(frame, "Glider heading", &names, selected);
+    }
+
+    fn draw_menu(&self, frame: &mut Frame, title: &str, names: &[&str], selected: usize) {
+        let lines: Vec<Line> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                if i == selected {
+                    Line::from(format!("> {name}")).bold().fg(self.theme.accent)
+                } else {
+                    Line::from(format!("  {name}"))
+                }
+            })
+            .collect();
+        let longest = names.iter().map(|name| name.chars().count()).max().unwrap_or(0);
+        let width = (longest + 4).max(title.chars().count() + 2) as u16;
+
+        let [area] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+    }
+
+    /// Splits the screen into the instruction line, the board, the
+    /// information line and the population sparkline. The information line
+    /// gets no height while it is hidden.
+    fn screen_layout(&self, area: Rect) -> Rc<[Rect]> {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(u16::from(self.show_status)),
+                Constraint::Length(1),
+            ]).split(area)
+    }
 
-        frame.render_widget(instructions, layout[0]);
-        frame.render_widget(self, layout[1]);
-        frame.render_widget(information, layout[2]);
+    fn centered_board_area(&self, area: Rect) -> Rect {
+        let [area] = Layout::horizontal([Constraint::Length((self.board.size.1 * self.cell_width()) as u16)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(self.board.size.0 as u16)])
+            .flex(Flex::Center)
+            .areas(area);
+        area
     }
 
-    fn handle_events(&mut self) -> io::Result<()> {
-        if poll(Duration::from_millis(0))? {
+    fn handle_events(&mut self, timeout: Duration) -> io::Result<()> {
+        if poll(timeout)? {
             match event::read()? {
+                // Only the size message is drawn then, so keys other than
+                // quitting would edit a board out of sight.
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press && self.terminal_too_small() => {
+                    self.exit = key_event.code == KeyCode::Char('q');
+                }
                 Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                     self.handle_key_event(key_event)
                 }
+                Event::Mouse(_) if self.terminal_too_small() => {}
+                Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+                Event::Resize(width, height) => self.handle_resize(width, height),
                 _ => {}
             };
         }
         Ok(())
     }
 
+    /// Follows the terminal size: the grid is reallocated to fit the new
+    /// viewport, keeping the overlapping cells.
+    fn handle_resize(&mut self, width: u16, height: u16) {
+        self.terminal_size = (height as usize, width as usize);
+        self.resize_grid(self.world_size());
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        if self.show_help {
+            return;
+        }
+        if let MouseEventKind::Up(MouseButton::Left) = mouse_event.kind {
+            self.mouse_stroke = None;
+            return;
+        }
+        let Some((x, y)) = self.screen_to_grid(mouse_event.column, mouse_event.row) else {
+            return;
+        };
+
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.record_edit();
+                self.toggle_cell(x, y);
+                self.mouse_stroke = Some((x, y));
+            }
+            MouseEventKind::Drag(MouseButton::Left) => self.drag_stroke((x, y)),
+            _ => {}
+        }
+    }
+
+    /// Sets the cells on the line from the last stroke position to `to`
+    /// alive, `brush_size` wide. The whole stroke shares the undo entry
+    /// recorded when the button went down.
+    fn drag_stroke(&mut self, to: (usize, usize)) {
+        let Some(from) = self.mouse_stroke.replace(to) else {
+            return;
+        };
+        let start = -((self.brush_size as i32 - 1) / 2);
+        let end = start + self.brush_size as i32 - 1;
+        for (x, y) in line_cells(from, to) {
+            for dx in start..=end {
+                for dy in start..=end {
+                    if let Some((real_x, real_y)) = self.neighbour_position(x as i32 + dx, y as i32 + dy) {
+                        self.board.cells[real_x][real_y] = ALIVE;
+                    }
+                }
+            }
+        }
+        self.reset_period_detection();
+        self.all_dirty = true;
+    }
+
+    /// Converts a terminal position to grid coordinates, going through the
+    /// same layout as `draw` so the header line and centering are skipped.
+    fn screen_to_grid(&self, column: u16, row: u16) -> Option<(usize, usize)> {
+        let (height, width) = self.terminal_size;
+        let layout = self.screen_layout(Rect::new(0, 0, width as u16, height as u16));
+        let board_area = self.centered_board_area(layout[1]);
+        if !board_area.contains(Position::new(column, row)) {
+            return None;
+        }
+
+        let x = self.viewport_origin.0 + (row - board_area.y) as usize;
+        let y = self.viewport_origin.1 + (column - board_area.x) as usize / self.cell_width();
+        (x < self.board.size.0 && y < self.board.size.1).then_some((x, y))
+    }
+
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if self.prompt.is_some() {
+            self.handle_prompt_key_event(key_event);
+            return;
+        }
+        if self.pattern_menu.is_some() {
+            self.handle_pattern_menu_key_event(key_event);
+            return;
+        }
+        if self.glider_menu.is_some() {
+            self.handle_glider_menu_key_event(key_event);
+            return;
+        }
+        let clear_walls_armed = std::mem::take(&mut self.clear_walls_armed);
+        let confirmed = |armed_at: Option<Instant>| armed_at.is_some_and(|armed_at| armed_at.elapsed() <= App::CONFIRM_WINDOW);
+        let reset_confirmed = confirmed(self.reset_armed.take());
+        let regenerate_confirmed = confirmed(self.regenerate_armed.take());
+        let slot_restore_armed = std::mem::take(&mut self.slot_restore_armed);
+        let count_prefix = self.count_prefix.take();
+        if self.show_help {
+            match key_event.code {
+                KeyCode::Char('q') => self.exit(),
+                KeyCode::Char('?') | KeyCode::Esc => self.show_help = false,
+                _ => {}
+            }
+            return;
+        }
+
         match key_event.code {
+            KeyCode::Char(digit @ '1'..='9') if slot_restore_armed => self.restore_slot(digit_index(digit)),
+            KeyCode::Char(digit @ '1'..='9') if key_event.modifiers == KeyModifiers::ALT => {
+                self.save_slot(digit_index(digit))
+            }
+            KeyCode::Char('\'') => self.slot_restore_armed = true,
+            KeyCode::Char(digit @ '0'..='9')
+                if self.game_pause && key_event.modifiers == KeyModifiers::NONE && (digit != '0' || count_prefix.is_some()) =>
+            {
+                let digit = u64::from(digit.to_digit(10).unwrap());
+                let count = count_prefix.unwrap_or(0) * 10 + digit;
+                self.count_prefix = Some(count.min(App::MAX_STEP_COUNT));
+            }
+            KeyCode::Char(digit @ '1'..='9') => self.update_per_second_max = App::SPEED_PRESETS[digit_index(digit)],
             KeyCode::Char('q') => self.exit(),
             KeyCode::Char(' ') => self.toggle_game_pause(),
+            KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down
+                if key_event.modifiers == KeyModifiers::CONTROL => self.nudge_game_table(key_event.code),
+            KeyCode::Left if key_event.modifiers == KeyModifiers::ALT => self.pan(0, -1),
+            KeyCode::Right if key_event.modifiers == KeyModifiers::ALT => self.pan(0, 1),
+            KeyCode::Up if key_event.modifiers == KeyModifiers::ALT => self.pan(-1, 0),
+            KeyCode::Down if key_event.modifiers == KeyModifiers::ALT => self.pan(1, 0),
             KeyCode::Left => self.game_table_user_cursor_move(key_event.code, key_event),
             KeyCode::Right => self.game_table_user_cursor_move(key_event.code, key_event),
             KeyCode::Up => self.game_table_user_cursor_move(key_event.code, key_event),
             KeyCode::Down => self.game_table_user_cursor_move(key_event.code, key_event),
-            KeyCode::Char('s') => self.switch_cell_state(),
-            KeyCode::Char('a') => self.decrease_update_per_second_max(1),
-            KeyCode::Char('d') => self.increase_update_per_second_max(1),
+            KeyCode::Home | KeyCode::End | KeyCode::PageUp | KeyCode::PageDown => {
+                self.game_table_user_cursor_move(key_event.code, key_event)
+            }
+            KeyCode::Char('s') if key_event.modifiers != KeyModifiers::CONTROL => self.switch_cell_state(),
+            KeyCode::Char('|') => self.cycle_symmetry(),
+            KeyCode::Char('#') => self.show_column_histogram = !self.show_column_histogram,
+            KeyCode::Char('B') => self.show_edges = !self.show_edges,
+            KeyCode::Char('a') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.decrease_update_per_second_max(App::UPDATE_PER_SECOND_LARGE_STEP)
+            }
+            KeyCode::Char('d') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.increase_update_per_second_max(App::UPDATE_PER_SECOND_LARGE_STEP)
+            }
+            KeyCode::Char('a') => self.decrease_update_per_second_max(1.0),
+            KeyCode::Char('d') => self.increase_update_per_second_max(1.0),
+            KeyCode::Char('U') => self.ramp_pressed_at = Some(Instant::now()),
+            KeyCode::Char('/') => self.scale_update_per_second_max(0.5),
+            KeyCode::Char('*') => self.scale_update_per_second_max(2.0),
+            KeyCode::Char('r') if key_event.modifiers == KeyModifiers::CONTROL => self.redo(),
             KeyCode::Char('r') => self.reset_update_per_second_max(),
-            KeyCode::Char('t') => self.toggle_step_by_step(),
-            KeyCode::Char('n') => self.reset_game_table(),
+            KeyCode::Char('t') => self.toggle_step_by_step(count_prefix.unwrap_or(1)),
+            KeyCode::Char('T') => self.step_backward(),
+            KeyCode::Char('n') if clear_walls_armed => self.clear_walls(),
+            // Clearing the area between the two cursors can be undone, so
+            // only clearing the whole board asks first.
+            KeyCode::Char('n') if reset_confirmed || self.game_table_user_cursor != self.game_table_user_cursor2 => {
+                self.reset_game_table()
+            }
+            KeyCode::Char('n') => {
+                self.reset_armed = Some(Instant::now());
+                self.notice = Some("press <n> again to clear the board".to_string());
+            }
+            KeyCode::Char('e') if key_event.modifiers == KeyModifiers::CONTROL => self.export_to_system_clipboard(),
             KeyCode::Char('e') => self.save_selected_table(),
             KeyCode::Char('i') => self.import_selected_table(),
+            KeyCode::Char('x') => self.toggle_square_world(),
+            KeyCode::Char('k') => self.load_kernel(),
+            KeyCode::Char('K') => self.clear_kernel(),
+            KeyCode::Char('v') => self.toggle_show_never_changed(),
+            KeyCode::Char('J') => self.toggle_smooth_death(),
+            KeyCode::Char('o') => self.cycle_update_order(),
+            KeyCode::Char('[') => self.change_birth_prob(-App::PROBABILITY_STEP),
+            KeyCode::Char(']') => self.change_birth_prob(App::PROBABILITY_STEP),
+            KeyCode::Char('{') => self.change_survive_prob(-App::PROBABILITY_STEP),
+            KeyCode::Char('}') => self.change_survive_prob(App::PROBABILITY_STEP),
+            KeyCode::Char('w') => self.save_rle_to_default_file(),
+            KeyCode::Char('E') => self.export_png_to_timestamped_file(),
+            KeyCode::Char('D') => self.toggle_recording(),
+            KeyCode::Char('s') if key_event.modifiers == KeyModifiers::CONTROL => self.save_session_to_default_file(),
+            KeyCode::Char('l') if key_event.modifiers == KeyModifiers::CONTROL => self.load_session_from_default_file(),
+            KeyCode::Char('l') => self.load_rle_from_default_file(),
+            KeyCode::Char('L') => self.load_cells_from_default_file(),
+            KeyCode::Char('p') if key_event.modifiers == KeyModifiers::CONTROL => self.launch_glider_gun_demo(),
+            KeyCode::Char('p') => self.pattern_menu = Some(0),
+            KeyCode::Char('b') => self.cycle_boundary(),
+            KeyCode::Char('R') => self.open_prompt(PromptKind::Rule),
+            KeyCode::Char('H') => self.open_prompt(PromptKind::AliveColor),
+            KeyCode::Char('+') => self.grow_grid(),
+            KeyCode::Char('-') => self.shrink_grid(),
+            KeyCode::Char('<') => self.decrease_brush_size(),
+            KeyCode::Char('>') => self.increase_brush_size(),
+            KeyCode::Char('m') => self.toggle_edit_mode(),
+            KeyCode::Char('z') => self.toggle_selection_start(),
+            KeyCode::Char('f') if key_event.modifiers == KeyModifiers::CONTROL => self.fit_view_to_pattern(),
+            KeyCode::Char('f') => self.fill_selection(true),
+            KeyCode::Char('F') => self.fill_selection(false),
+            KeyCode::Char('Z') => self.fill_selection_randomly(),
+            KeyCode::Char('c') => self.copy_selection(),
+            KeyCode::Char('y') => self.paste_clipboard(false),
+            KeyCode::Char('Y') => self.paste_clipboard(true),
+            KeyCode::Char('O') => self.rotate_clipboard(),
+            KeyCode::Char('X') => self.flip_clipboard(true),
+            KeyCode::Char('V') => self.flip_clipboard(false),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('N') if regenerate_confirmed || !self.confirm_regenerate => self.regenerate_game_table(),
+            KeyCode::Char('N') => {
+                self.regenerate_armed = Some(Instant::now());
+                self.notice = Some("press <N> again for a new soup".to_string());
+            }
+            KeyCode::Char('(') => self.change_fill_density(-App::PROBABILITY_STEP),
+            KeyCode::Char(')') => self.change_fill_density(App::PROBABILITY_STEP),
+            KeyCode::Char('h') => self.toggle_heatmap(),
+            KeyCode::Char('G') => self.cycle_glyphs(),
+            KeyCode::Char('g') if key_event.modifiers == KeyModifiers::CONTROL => self.cycle_grid_spacing(),
+            KeyCode::Char('g') => self.toggle_grid(),
+            KeyCode::Char('M') => self.cycle_theme(),
+            KeyCode::Char('j') => self.fast_forward(),
+            KeyCode::Char('S') => self.toggle_pause_on_period(),
+            KeyCode::Char('?') => self.show_help = true,
+            KeyCode::Char('W') => self.paint_walls(),
+            KeyCode::Char(',') => self.change_range(-1),
+            KeyCode::Char('.') => self.change_range(1),
+            KeyCode::Char('A') => self.toggle_aspect_correct(),
+            KeyCode::Char('I') => self.toggle_status(),
+            KeyCode::Char('C') => self.center_cursor(),
+            KeyCode::Char('!') => self.invert_board(),
+            KeyCode::Char('P') => self.toggle_parallel(),
             _ => {}
         }
     }
 
-    fn count_number_of_neighbour(&self, game_table: &GameTable, x: u16, y: u16) -> u8 {
-        let xi32 = x as i32;
-        let yi32 = y as i32;
-        let game_table_size0i32 = self.game_table_size.0 as i32;
-        let game_table_size1i32 = self.game_table_size.1 as i32;
-        let mut count = 0;
-        for iy in -1..=1 {
-            for ix in -1..=1 {
-                if iy == 0 && ix == 0 { continue };
-                let real_x = (xi32 + ix + game_table_size0i32) % game_table_size0i32;
-                let real_y = (yi32 + iy + game_table_size1i32) % game_table_size1i32;
-
-                if game_table[real_x as usize][real_y as usize] {
-                    count += 1;
-                }
-            }
-        }
-
-        count
+    fn open_prompt(&mut self, kind: PromptKind) {
+        self.prompt = Some(Prompt { kind, input: String::new() });
     }
 
-    fn update_game_table(&self, game_table: GameTable) -> GameTable {
-        let mut new_game_table: GameTable = initialize_empty_game_table(self.game_table_size);
+    fn handle_prompt_key_event(&mut self, key_event: KeyEvent) {
+        let Some(prompt) = &mut self.prompt else {
+            return;
+        };
 
-        for (x, row) in game_table.iter().enumerate() {
-            for (y, cell) in row.iter().enumerate() {
-                let neighbour = self.count_number_of_neighbour(&game_table, x as u16, y as u16);
-                let new_cell_state = &mut new_game_table[x][y];
-                match (neighbour, *cell) {
-                    (2 | 3, true) => *new_cell_state = true,
-                    (3, false) => *new_cell_state = true,
-                    (_, _) => ()
+        match key_event.code {
+            KeyCode::Char(character) => prompt.input.push(character),
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Enter => {
+                if let Some(prompt) = self.prompt.take() {
+                    self.submit_prompt(prompt);
                 }
             }
+            KeyCode::Esc => self.prompt = None,
+            _ => {}
         }
-
-        new_game_table
     }
 
-    fn toggle_game_pause(&mut self) {
-        self.game_pause = !self.game_pause;
+    fn submit_prompt(&mut self, prompt: Prompt) {
+        match prompt.kind {
+            PromptKind::Rule => match Rule::parse(&prompt.input) {
+                Ok(rule) => {
+                    self.rule = rule;
+                    self.notice = None;
+                }
+                Err(error) => self.notice = Some(error),
+            },
+            PromptKind::AliveColor => match parse_hex_color(&prompt.input) {
+                Ok(color) => {
+                    self.theme.alive = color;
+                    self.notice = None;
+                }
+                Err(error) => self.notice = Some(error),
+            },
+        }
     }
 
-    fn game_table_user_cursor_move(&mut self, direction: KeyCode, key_event: KeyEvent) {
-        let (x, y) = if key_event.modifiers == KeyModifiers::SHIFT {
-            (&mut self.game_table_user_cursor2.0, &mut self.game_table_user_cursor2.1)
-        } else {
-            (&mut self.game_table_user_cursor.0, &mut self.game_table_user_cursor.1)
+    fn handle_pattern_menu_key_event(&mut self, key_event: KeyEvent) {
+        let Some(selected) = self.pattern_menu else {
+            return;
         };
 
-        match direction {
-            KeyCode::Up => {
-                if *x > 0 {
-                    *x -= 1;
-                } else {
-                    *x = self.game_table_size.0 - 1;
-                }
-            }
-            KeyCode::Down => {
-                if *x < self.game_table_size.0 - 1 {
-                    *x += 1;
-                } else {
-                    *x = 0;
-                }
-            }
-            KeyCode::Left => {
-                if *y > 0 {
-                    *y -= 1;
-                } else {
-                    *y = self.game_table_size.1 - 1;
-                }
+        match key_event.code {
+            KeyCode::Up => self.pattern_menu = Some((selected + PATTERNS.len() - 1) % PATTERNS.len()),
+            KeyCode::Down => self.pattern_menu = Some((selected + 1) % PATTERNS.len()),
+            KeyCode::Left => self.pattern_orientation = self.pattern_orientation.turned(3),
+            KeyCode::Right => self.pattern_orientation = self.pattern_orientation.turned(1),
+            KeyCode::Char('f') => self.pattern_orientation.flipped = !self.pattern_orientation.flipped,
+            KeyCode::Enter if PATTERNS[selected].name == GLIDER.name => {
+                self.pattern_menu = None;
+                self.glider_menu = Some(0);
             }
-            KeyCode::Right => {
-                if *y < self.game_table_size.1 - 1 {
-                    *y += 1;
-                } else {
-                    *y = 0;
-                }
+            KeyCode::Enter => {
+                self.place_pattern(&PATTERNS[selected], self.game_table_user_cursor, self.pattern_orientation);
+                self.pattern_menu = None;
             }
+            KeyCode::Esc => self.pattern_menu = None,
             _ => {}
         }
+    }
 
-        if key_event.modifiers != KeyModifiers::SHIFT {
-            self.game_table_user_cursor2.0 = self.game_table_user_cursor.0;
-            self.game_table_user_cursor2.1 = self.game_table_user_cursor.1;
+    /// Picks the direction a glider placed from the pattern menu flies in.
+    fn handle_glider_menu_key_event(&mut self, key_event: KeyEvent) {
+        let Some(selected) = self.glider_menu else {
+            return;
+        };
+
+        let count = Orientation::GLIDER_DIRECTIONS.len();
+        match key_event.code {
+            KeyCode::Up => self.glider_menu = Some((selected + count - 1) % count),
+            KeyCode::Down => self.glider_menu = Some((selected + 1) % count),
+            KeyCode::Enter => {
+                let (_, orientation) = Orientation::GLIDER_DIRECTIONS[selected];
+                self.place_pattern(&GLIDER, self.game_table_user_cursor, orientation);
+                self.glider_menu = None;
+            }
+            KeyCode::Esc => self.glider_menu = None,
+            _ => {}
         }
     }
 
-    fn print_game_table(&self) -> Text {
-        let mut lines = Vec::new();
+    /// Maps a possibly out-of-range neighbour position onto the board,
+    /// following `self.boundary`.
+    fn neighbour_position(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        self.board.neighbour_position(self.boundary, x, y)
+    }
 
-        for (x, row) in self.game_table.iter().enumerate() {
-            let mut spans = Vec::new();
-            for (y, cell) in row.iter().enumerate() {
-                let character = if *cell { "#" } else { " " };
-                let span = if self.game_pause
-                    && ((x == self.game_table_user_cursor.0 && y == self.game_table_user_cursor.1)
-                    || (x == self.game_table_user_cursor2.0 && y == self.game_table_user_cursor2.1))
-                {
-                    Span::styled(character, Style::default().bg(Color::LightGreen))
-                } else {
-                    if self.game_pause && self.is_inside_user_cursor(x, y) {
-                        Span::styled(character, Style::default().bg(Color::Green))
-                    } else {
-                        Span::styled(character, Style::default())
-                    }
+    fn kernel_weighted_sum(&self, board: &Board, kernel: &Kernel, x: usize, y: usize) -> i32 {
+        let center_x = (kernel.weights.len() / 2) as i32;
+        let center_y = (kernel.weights[0].len() / 2) as i32;
+        let mut sum = 0;
+        for (kx, weights) in kernel.weights.iter().enumerate() {
+            for (ky, weight) in weights.iter().enumerate() {
+                let real_x = x as i32 + kx as i32 - center_x;
+                let real_y = y as i32 + ky as i32 - center_y;
+                let Some((real_x, real_y)) = board.neighbour_position(self.boundary, real_x, real_y) else {
+                    continue;
                 };
-                spans.push(span);
+                if board.cells[real_x][real_y] == ALIVE || self.walls[real_x][real_y] {
+                    sum += weight;
+                }
             }
-            lines.push(Line::from(spans));
         }
 
-        Text::from(lines)
+        sum
+    }
+
+    /// State the rule, or the kernel when one is loaded, gives the cell,
+    /// before any birth or survival chance is drawn. `neighbour` is unused
+    /// with a kernel, which weighs the cells itself.
+    fn next_cell_state(&self, board: &Board, x: usize, y: usize, neighbour: u8) -> Cell {
+        let cell = board.cells[x][y];
+        if let Some(kernel) = &self.kernel {
+            if cell > ALIVE {
+                return self.rule.decay(cell);
+            }
+            let sum = self.kernel_weighted_sum(board, kernel, x, y);
+            return match (cell == ALIVE, kernel.next_state(sum, cell == ALIVE)) {
+                (_, true) => ALIVE,
+                (true, false) => self.rule.decay(ALIVE),
+                (false, false) => DEAD,
+            };
+        }
+
+        self.rule.next_cell(cell, neighbour)
+    }
+
+    /// Lets a birth or a survival happen only with its configured probability.
+    ///
+    /// The draws come from `self.rng`, so a run is reproducible as long as
+    /// the RNG is seeded the same way. No draw is made for a probability of
+    /// 1.0, which keeps the default rule fully deterministic.
+    fn apply_chance(&mut self, alive: bool, next_alive: bool) -> bool {
+        let probability = match (alive, next_alive) {
+            (false, true) => self.birth_prob,
+            (true, true) => self.survive_prob,
+            (_, false) => return false,
+        };
+        probability >= 1.0 || self.rng.random_bool(probability)
+    }
+
+    /// Computes the next generation into the back buffer, reading from the
+    /// front one, then swaps them: steady-state steps neither clone nor
+    /// allocate.
+    fn update_game_table(&mut self) {
+        self.changed_cells.clear();
+        #[cfg(feature = "parallel")]
+        if self.parallel {
+            self.all_dirty = true;
+            return self.update_game_table_parallel();
+        }
+
+        // Only a deterministic rule lets cells away from any change be
+        // skipped: a chance draw or a kernel can change any of them. Past a
+        // few dirty cells the scatter count of the full pass is faster.
+        let deterministic = self.kernel.is_none() && self.birth_prob >= 1.0 && self.survive_prob >= 1.0;
+        let signature = (self.rule, self.boundary, self.range);
+        let few_dirty = self.dirty_cells.len() * App::DIRTY_CELLS_FRACTION < self.board.size.0 * self.board.size.1;
+        if deterministic && !self.all_dirty && self.dirty_signature == Some(signature) && few_dirty {
+            self.update_dirty_cells();
+        } else {
+            self.update_all_cells();
+        }
+
+        if deterministic {
+            self.collect_dirty_cells();
+            self.all_dirty = false;
+            self.dirty_signature = Some(signature);
+        } else {
+            self.all_dirty = true;
+        }
+    }
+
+    fn update_all_cells(&mut self) {
+        let board = std::mem::take(&mut self.board);
+        let mut back_board = std::mem::take(&mut self.back_board);
+        let mut neighbour_counts = std::mem::take(&mut self.neighbour_counts);
+        if self.kernel.is_none() {
+            board.neighbour_counts(&mut neighbour_counts, self.range, self.boundary, Some(&self.walls));
+        }
+        board.update_game_table(&mut back_board, |board, x, y| {
+            let neighbour = neighbour_counts.get(x).map_or(0, |row| row[y]);
+            let new_cell_state = self.next_cell(board, x, y, neighbour);
+            self.track_cell(x, y, board.cells[x][y], new_cell_state);
+            new_cell_state
+        });
+
+        self.board = back_board;
+        self.back_board = board;
+        self.neighbour_counts = neighbour_counts;
+    }
+
+    /// Recomputes only `dirty_cells`, the cells near a change in the last
+    /// generation; every other cell keeps its state, since none of its
+    /// neighbours moved.
+    fn update_dirty_cells(&mut self) {
+        let board = std::mem::take(&mut self.board);
+        let mut back_board = std::mem::take(&mut self.back_board);
+        back_board.cells.clone_from(&board.cells);
+        back_board.size = board.size;
+        for ((cells, ages), dirty) in board.cells.iter().zip(&mut self.cell_age).zip(&self.dirty) {
+            for ((cell, age), dirty) in cells.iter().zip(ages).zip(dirty) {
+                if *cell == ALIVE && !dirty {
+                    *age = age.saturating_add(1);
+                }
+            }
+        }
+
+        let dirty_cells = std::mem::take(&mut self.dirty_cells);
+        for &(x, y) in &dirty_cells {
+            let neighbour = board.count_number_of_neighbour(x, y, self.range, self.boundary, Some(&self.walls));
+            let new_cell_state = self.next_cell(&board, x, y, neighbour);
+            self.track_cell(x, y, board.cells[x][y], new_cell_state);
+            back_board.cells[x][y] = new_cell_state;
+        }
+
+        self.dirty_cells = dirty_cells;
+        self.board = back_board;
+        self.back_board = board;
+    }
+
+    /// Marks the neighbourhood of every cell `track_cell` saw change as the
+    /// cells to recompute next generation.
+    fn collect_dirty_cells(&mut self) {
+        let (height, width) = self.board.size;
+        if self.dirty.len() != height || self.dirty.first().map(Vec::len) != Some(width) {
+            self.dirty = vec![vec![false; width]; height];
+            self.dirty_cells.clear();
+        }
+        for (x, y) in self.dirty_cells.drain(..) {
+            self.dirty[x][y] = false;
+        }
+
+        for &(x, y) in &self.changed_cells {
+            for dx in -self.range..=self.range {
+                for dy in -self.range..=self.range {
+                    let position = self.board.neighbour_position(self.boundary, x as i32 + dx, y as i32 + dy);
+                    if let Some((real_x, real_y)) = position
+                        && !self.dirty[real_x][real_y]
+                    {
+                        self.dirty[real_x][real_y] = true;
+                        self.dirty_cells.push((real_x, real_y));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Next state of the cell at `(x, y)` of `board`, which has `neighbour`
+    /// live neighbours. Walls never change; a birth or survival that loses
+    /// its chance draw turns into a death.
+    fn next_cell(&mut self, board: &Board, x: usize, y: usize, neighbour: u8) -> Cell {
+        let cell = board.cells[x][y];
+        if self.walls[x][y] {
+            return cell;
+        }
+
+        let next_cell = self.next_cell_state(board, x, y, neighbour);
+        self.draw_chance(cell, next_cell)
+    }
+
+    fn draw_chance(&mut self, cell: Cell, next_cell: Cell) -> Cell {
+        if next_cell == ALIVE && !self.apply_chance(cell == ALIVE, true) {
+            return if cell == ALIVE { self.rule.decay(ALIVE) } else { DEAD };
+        }
+        next_cell
+    }
+
+    /// `update_game_table` with the rows computed in parallel. The rule
+    /// only reads the front board, so rows are independent; the chance
+    /// draws and the tracking need `&mut self` and run afterwards, in the
+    /// same order as the serial update, which keeps runs reproducible.
+    #[cfg(feature = "parallel")]
+    fn update_game_table_parallel(&mut self) {
+        let board = std::mem::take(&mut self.board);
+        let mut back_board = std::mem::take(&mut self.back_board);
+        let mut neighbour_counts = std::mem::take(&mut self.neighbour_counts);
+        if self.kernel.is_none() {
+            board.par_neighbour_counts(&mut neighbour_counts, self.range, self.boundary, Some(&self.walls));
+        }
+        board.par_update_game_table(&mut back_board, |board, x, y| {
+            if self.walls[x][y] {
+                return board.cells[x][y];
+            }
+            let neighbour = neighbour_counts.get(x).map_or(0, |row| row[y]);
+            self.next_cell_state(board, x, y, neighbour)
+        });
+
+        for (x, row) in back_board.cells.iter_mut().enumerate() {
+            for (y, new_cell_state) in row.iter_mut().enumerate() {
+                let cell = board.cells[x][y];
+                if !self.walls[x][y] {
+                    *new_cell_state = self.draw_chance(cell, *new_cell_state);
+                }
+                self.track_cell(x, y, cell, *new_cell_state);
+            }
+        }
+
+        self.board = back_board;
+        self.back_board = board;
+        self.neighbour_counts = neighbour_counts;
+    }
+
+    fn toggle_parallel(&mut self) {
+        if cfg!(feature = "parallel") {
+            self.parallel = !self.parallel;
+        } else {
+            self.notice = Some("built without the parallel feature".to_string());
+        }
+    }
+
+    /// Records one cell's transition in the per-cell tracking grids.
+    fn track_cell(&mut self, x: usize, y: usize, cell: Cell, new_cell_state: Cell) {
+        if new_cell_state != cell {
+            self.ever_changed[x][y] = true;
+            self.changed_cells.push((x, y));
+        }
+        self.just_died[x][y] = cell == ALIVE && new_cell_state == DEAD;
+        let age = &mut self.cell_age[x][y];
+        *age = if cell == ALIVE && new_cell_state == ALIVE { age.saturating_add(1) } else { 0 };
+    }
+
+    /// Updates the cells one at a time, in place, in the given scan order.
+    ///
+    /// Unlike `update_game_table`, a cell sees the new state of the
+    /// neighbours visited before it, so the dynamics differ from Life.
+    fn update_game_table_sequential(&mut self, order: UpdateOrder) {
+        self.changed_cells.clear();
+        self.all_dirty = true;
+        let (height, width) = self.board.size;
+        let mut positions: Vec<(usize, usize)> = match order {
+            UpdateOrder::ColumnMajor => (0..width).flat_map(|y| (0..height).map(move |x| (x, y))).collect(),
+            _ => (0..height).flat_map(|x| (0..width).map(move |y| (x, y))).collect(),
+        };
+        if order == UpdateOrder::Random {
+            positions.shuffle(&mut self.rng);
+        }
+
+        let mut board = std::mem::take(&mut self.board);
+        for (x, y) in positions {
+            let cell = board.cells[x][y];
+            let neighbour = match self.kernel {
+                Some(_) => 0,
+                None => board.count_number_of_neighbour(x, y, self.range, self.boundary, Some(&self.walls)),
+            };
+            let new_cell_state = self.next_cell(&board, x, y, neighbour);
+            self.track_cell(x, y, cell, new_cell_state);
+            board.cells[x][y] = new_cell_state;
+        }
+        self.board = board;
+    }
+
+    fn change_birth_prob(&mut self, delta: f64) {
+        self.birth_prob = step_probability(self.birth_prob, delta);
+    }
+
+    fn change_fill_density(&mut self, delta: f64) {
+        self.fill_density = step_probability(self.fill_density, delta);
+    }
+
+    fn change_survive_prob(&mut self, delta: f64) {
+        self.survive_prob = step_probability(self.survive_prob, delta);
+    }
+
+    /// Neighbour counts mean something else at another range, so the
+    /// period detection restarts.
+    fn change_range(&mut self, delta: i32) {
+        self.range = (self.range + delta).clamp(1, MAX_RANGE);
+        self.reset_period_detection();
+    }
+
+    fn cycle_boundary(&mut self) {
+        self.boundary = match self.boundary {
+            Boundary::Toroidal => Boundary::Dead,
+            Boundary::Dead => Boundary::Toroidal,
+        };
+    }
+
+    fn cycle_symmetry(&mut self) {
+        self.symmetry = match self.symmetry {
+            Symmetry::None => Symmetry::Horizontal,
+            Symmetry::Horizontal => Symmetry::Vertical,
+            Symmetry::Vertical => Symmetry::Both,
+            Symmetry::Both => Symmetry::FourFold,
+            Symmetry::FourFold => Symmetry::None,
+        };
+    }
+
+    fn cycle_update_order(&mut self) {
+        self.update_order = match self.update_order {
+            UpdateOrder::Simultaneous => UpdateOrder::RowMajor,
+            UpdateOrder::RowMajor => UpdateOrder::ColumnMajor,
+            UpdateOrder::ColumnMajor => UpdateOrder::Random,
+            UpdateOrder::Random => UpdateOrder::Simultaneous,
+        };
+    }
+
+    fn step(&mut self) {
+        let previous_population = self.population();
+        self.push_history_entry();
+        if self.board_hashes.is_empty() {
+            self.board_hashes.push_back(hash_game_table(&self.board.cells));
+        }
+        match self.update_order {
+            UpdateOrder::Simultaneous => self.update_game_table(),
+            order => self.update_game_table_sequential(order),
+        }
+        self.generation += 1;
+        self.detect_period();
+        let population = self.population();
+        if self.peak_population.is_none_or(|(peak, _)| population > peak) {
+            self.peak_population = Some((population, self.generation));
+        }
+
+        while self.population_history.len() >= self.terminal_size.1.max(1) {
+            self.population_history.pop_front();
+        }
+        self.population_history.push_back(self.population() as u64);
+        self.record_frame();
+
+        // Only a generation that lost its last cells is an extinction: an
+        // empty board stepped again, as under a rule whose cells never die,
+        // is not one.
+        if self.population() == 0 && previous_population > 0 {
+            self.game_pause = true;
+            self.notice = Some(format!("extinct at generation {}", self.generation));
+        }
+    }
+
+    /// Runs `fast_forward_count` generations without drawing in between and
+    /// reports the whole batch in `fast_forward_time`. Stops early on
+    /// extinction.
+    fn fast_forward(&mut self) {
+        let fast_forward_t1 = Instant::now();
+        for _ in 0..self.fast_forward_count {
+            self.step();
+            if self.population() == 0 || self.check_stop_at() {
+                break;
+            }
+        }
+        self.fast_forward_time = Some(fast_forward_t1.elapsed());
+    }
+
+    fn record_update_time(&mut self, time_to_update: Duration) {
+        self.time_to_update = time_to_update;
+        if self.update_times.len() >= App::UPDATE_TIMES_WINDOW {
+            self.update_times.pop_front();
+        }
+        self.update_times.push_back(time_to_update);
+    }
+
+    /// Minimum, median and 99th percentile of `update_times`.
+    fn update_time_percentiles(&self) -> Option<(Duration, Duration, Duration)> {
+        let mut sorted: Vec<Duration> = self.update_times.iter().copied().collect();
+        sorted.sort_unstable();
+        let percentile = |fraction: f64| sorted[((sorted.len() - 1) as f64 * fraction).round() as usize];
+        Some((*sorted.first()?, percentile(0.5), percentile(0.99)))
+    }
+
+    fn population(&self) -> usize {
+        self.board.population()
+    }
+
+    /// Forgets everything tracked about the previous board, for when the
+    /// whole board gets replaced.
+    fn reset_board_tracking(&mut self) {
+        self.ever_changed = vec![vec![false; self.board.size.1]; self.board.size.0];
+        self.just_died = vec![vec![false; self.board.size.1]; self.board.size.0];
+        self.cell_age = vec![vec![0; self.board.size.1]; self.board.size.0];
+        self.nudge_offset = (0, 0);
+        self.generation = 0;
+        self.population_history.clear();
+        self.reset_period_detection();
+        self.all_dirty = true;
+    }
+
+    /// Also restarts the longevity stats, which only make sense for an
+    /// unedited run.
+    fn reset_period_detection(&mut self) {
+        self.board_hashes.clear();
+        self.detected_period = None;
+        self.stabilized_at = None;
+        self.peak_population = None;
+    }
+
+    /// Compares the board with the last `App::PERIOD_DETECTION_DEPTH` ones;
+    /// a match `p` generations back means the pattern has period `p`
+    /// (1 for a still life).
+    fn detect_period(&mut self) {
+        let hash = hash_game_table(&self.board.cells);
+        if let Some(position) = self.board_hashes.iter().rev().position(|previous| *previous == hash) {
+            let newly_detected = self.detected_period.is_none();
+            self.detected_period = Some(position as u64 + 1);
+            if newly_detected {
+                self.stabilized_at.get_or_insert(self.generation - (position as u64 + 1));
+            }
+            if newly_detected && self.pause_on_period {
+                self.game_pause = true;
+            }
+        }
+
+        if self.board_hashes.len() == App::PERIOD_DETECTION_DEPTH {
+            self.board_hashes.pop_front();
+        }
+        self.board_hashes.push_back(hash);
+    }
+
+    fn toggle_pause_on_period(&mut self) {
+        self.pause_on_period = !self.pause_on_period;
+    }
+
+    fn cycle_glyphs(&mut self) {
+        let current = App::GLYPH_PRESETS
+            .iter()
+            .position(|glyphs| *glyphs == (self.alive_char, self.dead_char))
+            .unwrap_or(0);
+        (self.alive_char, self.dead_char) = App::GLYPH_PRESETS[(current + 1) % App::GLYPH_PRESETS.len()];
+    }
+
+    fn cycle_theme(&mut self) {
+        let current = Theme::PRESETS.iter().position(|theme| *theme == self.theme).unwrap_or(0);
+        self.theme = Theme::PRESETS[(current + 1) % Theme::PRESETS.len()];
+        self.notice = Some(format!("{} theme", self.theme.name));
+    }
+
+    fn toggle_grid(&mut self) {
+        self.show_grid = !self.show_grid;
+    }
+
+    fn cycle_grid_spacing(&mut self) {
+        let current = App::GRID_SPACINGS.iter().position(|spacing| *spacing == self.grid_spacing).unwrap_or(0);
+        self.grid_spacing = App::GRID_SPACINGS[(current + 1) % App::GRID_SPACINGS.len()];
+        self.show_grid = true;
+    }
+
+    fn toggle_heatmap(&mut self) {
+        self.show_heatmap = !self.show_heatmap;
+    }
+
+    fn toggle_show_never_changed(&mut self) {
+        self.show_never_changed = !self.show_never_changed;
+    }
+
+    fn toggle_smooth_death(&mut self) {
+        self.smooth_death = !self.smooth_death;
+        self.notice = Some(format!("smooth death {}", if self.smooth_death { "on" } else { "off" }));
+    }
+
+    /// Edits are always allowed while paused, and while running only in
+    /// edit mode, where they apply from the next generation on.
+    fn is_editing(&self) -> bool {
+        self.game_pause || self.edit_mode
+    }
+
+    fn toggle_edit_mode(&mut self) {
+        self.edit_mode = !self.edit_mode;
+    }
+
+    fn toggle_game_pause(&mut self) {
+        self.game_pause = !self.game_pause;
+        if !self.game_pause && self.stop_at.is_some_and(|stop_at| self.generation >= stop_at) {
+            self.stop_at = None;
+        }
+    }
+
+    /// Pauses once the generation reaches `stop_at`. Resuming clears the
+    /// target, so the run then goes on.
+    fn check_stop_at(&mut self) -> bool {
+        let reached = self.stop_at.is_some_and(|stop_at| self.generation >= stop_at);
+        if reached {
+            self.game_pause = true;
+            self.notice = Some(format!("stopped at generation {}", self.generation));
+        }
+        reached
+    }
+
+    fn game_table_user_cursor_move(&mut self, direction: KeyCode, key_event: KeyEvent) {
+        let (x, y) = if key_event.modifiers == KeyModifiers::SHIFT {
+            (&mut self.game_table_user_cursor2.0, &mut self.game_table_user_cursor2.1)
+        } else {
+            (&mut self.game_table_user_cursor.0, &mut self.game_table_user_cursor.1)
+        };
+
+        match direction {
+            KeyCode::Up => {
+                if *x > 0 {
+                    *x -= 1;
+                } else {
+                    *x = self.board.size.0 - 1;
+                }
+            }
+            KeyCode::Down => {
+                if *x < self.board.size.0 - 1 {
+                    *x += 1;
+                } else {
+                    *x = 0;
+                }
+            }
+            KeyCode::Left => {
+                if *y > 0 {
+                    *y -= 1;
+                } else {
+                    *y = self.board.size.1 - 1;
+                }
+            }
+            KeyCode::Right => {
+                if *y < self.board.size.1 - 1 {
+                    *y += 1;
+                } else {
+                    *y = 0;
+                }
+            }
+            KeyCode::Home => *y = 0,
+            KeyCode::End => *y = self.board.size.1 - 1,
+            KeyCode::PageUp => *x = 0,
+            KeyCode::PageDown => *x = self.board.size.0 - 1,
+            _ => {}
+        }
+
+        if key_event.modifiers != KeyModifiers::SHIFT {
+            self.game_table_user_cursor2.0 = self.game_table_user_cursor.0;
+            self.game_table_user_cursor2.1 = self.game_table_user_cursor.1;
+            self.follow_cursor(self.game_table_user_cursor);
+        } else {
+            self.follow_cursor(self.game_table_user_cursor2);
+        }
+    }
+
+    /// Puts both cursors on the middle cell of the grid.
+    fn center_cursor(&mut self) {
+        self.game_table_user_cursor = (self.board.size.0 / 2, self.board.size.1 / 2);
+        self.game_table_user_cursor2 = self.game_table_user_cursor;
+        self.follow_cursor(self.game_table_user_cursor);
+    }
+
+    /// Rotates the whole board by one cell, wrapping toroidally.
+    fn nudge_game_table(&mut self, direction: KeyCode) {
+        if !self.game_pause {
+            return;
+        }
+        self.record_edit();
+
+        match direction {
+            KeyCode::Up => {
+                self.board.cells.rotate_left(1);
+                self.nudge_offset.0 -= 1;
+            }
+            KeyCode::Down => {
+                self.board.cells.rotate_right(1);
+                self.nudge_offset.0 += 1;
+            }
+            KeyCode::Left => {
+                self.board.cells.iter_mut().for_each(|row| row.rotate_left(1));
+                self.nudge_offset.1 -= 1;
+            }
+            KeyCode::Right => {
+                self.board.cells.iter_mut().for_each(|row| row.rotate_right(1));
+                self.nudge_offset.1 += 1;
+            }
+            _ => {}
+        }
+    }
+
+    /// One bar per column shown in `area`, as tall as its share of live
+    /// cells relative to the fullest visible column.
+    fn column_histogram(&self, area: Rect) -> Line<'_> {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let origin_y = self.viewport_origin.1;
+        let columns = (area.width as usize / self.cell_width()).min(self.board.size.1.saturating_sub(origin_y));
+        let counts: Vec<usize> = (origin_y..origin_y + columns)
+            .map(|y| self.board.cells.iter().filter(|row| row[y] == ALIVE).count())
+            .collect();
+        let highest = counts.iter().copied().max().unwrap_or(0).max(1);
+        let bars: String = counts
+            .iter()
+            .map(|count| match count {
+                0 => ' ',
+                count => BARS[(count * BARS.len()).div_ceil(highest) - 1],
+            })
+            .flat_map(|bar| std::iter::repeat_n(bar, self.cell_width()))
+            .collect();
+        Line::from(bars)
+    }
+
+    /// Renders the part of the grid that fits in `area`, starting at
+    /// `viewport_origin`.
+    fn print_game_table(&self, area: Rect) -> Text<'_> {
+        let mut lines = Vec::new();
+        let (origin_x, origin_y) = self.viewport_origin;
+
+        for (x, row) in self.board.cells.iter().enumerate().skip(origin_x).take(area.height as usize) {
+            let mut spans = Vec::new();
+            for (y, cell) in row.iter().enumerate().skip(origin_y).take(area.width as usize / self.cell_width()) {
+                let glyph = if *cell == DEAD { self.dead_char } else { self.alive_char };
+                let character = glyph.to_string().repeat(self.cell_width());
+                let span = if self.is_editing()
+                    && ((x == self.game_table_user_cursor.0 && y == self.game_table_user_cursor.1)
+                    || (x == self.game_table_user_cursor2.0 && y == self.game_table_user_cursor2.1))
+                {
+                    Span::styled(character, Style::default().bg(self.theme.cursor))
+                } else {
+                    if self.is_editing() && self.is_inside_user_cursor(x, y) {
+                        Span::styled(character, Style::default().bg(self.theme.brush))
+                    } else if self.is_inside_selection(x, y) {
+                        Span::styled(character, Style::default().bg(Color::Blue))
+                    } else if self.walls[x][y] {
+                        Span::styled(
+                            self.alive_char.to_string().repeat(self.cell_width()),
+                            Style::default().fg(Color::Black).bg(Color::Yellow),
+                        )
+                    } else if self.show_never_changed && !self.ever_changed[x][y] {
+                        Span::styled(character, Style::default().fg(Color::Gray).bg(Color::DarkGray))
+                    } else if self.smooth_death && self.just_died[x][y] && *cell == DEAD {
+                        // Only drawn: the cell is dead for the rule and the
+                        // population.
+                        Span::styled(self.alive_char.to_string().repeat(self.cell_width()), Style::default().fg(Color::Indexed(238)))
+                    } else {
+                        let mut style = Style::default();
+                        if *cell > ALIVE {
+                            style = style.fg(dying_color(*cell, self.rule.states));
+                        } else if self.show_heatmap && *cell == ALIVE {
+                            style = style.fg(age_color(self.cell_age[x][y]));
+                        } else if *cell == ALIVE {
+                            style = style.fg(self.theme.alive);
+                        }
+                        let (height, width) = self.board.size;
+                        if self.show_edges && (x == 0 || y == 0 || x == height - 1 || y == width - 1) {
+                            style = style.bg(match self.boundary {
+                                Boundary::Toroidal => Color::Indexed(24),
+                                Boundary::Dead => Color::Indexed(52),
+                            });
+                        } else if self.show_grid && (x % self.grid_spacing == 0 || y % self.grid_spacing == 0) {
+                            style = style.bg(Color::Indexed(236));
+                        }
+                        Span::styled(character, style)
+                    }
+                };
+                spans.push(span);
+            }
+            lines.push(Line::from(spans));
+        }
+
+        Text::from(lines)
     }
 
     fn switch_cell_state(&mut self) {
-        if self.game_pause {
-            let x = self.game_table_user_cursor.0;
-            let y = self.game_table_user_cursor.1;
+        if self.is_editing() {
+            self.paint_brush();
+        }
+    }
+
+    /// Flips every cell of the `brush_size` square centered on the cursor,
+    /// and their `symmetry` images. A cell reached more than once is still
+    /// flipped once. At the edges the square wraps or is cut off, like
+    /// neighbours are.
+    fn paint_brush(&mut self) {
+        self.record_edit();
+        let (x, y) = self.game_table_user_cursor;
+        let start = -((self.brush_size as i32 - 1) / 2);
+        let end = start + self.brush_size as i32 - 1;
+        let mut cells = Vec::new();
+        for dx in start..=end {
+            for dy in start..=end {
+                if let Some(position) = self.neighbour_position(x as i32 + dx, y as i32 + dy) {
+                    cells.extend(self.symmetry.images(position, self.board.size));
+                }
+            }
+        }
+        cells.sort_unstable();
+        cells.dedup();
+        for (x, y) in cells {
+            self.toggle_cell(x, y);
+        }
+    }
+
+    /// Flips walls over the brush square at the cursor. Cells turned into
+    /// walls are cleared, so a wall never hides a live cell.
+    fn paint_walls(&mut self) {
+        if !self.is_editing() {
+            return;
+        }
+        self.record_edit();
+        let (x, y) = self.game_table_user_cursor;
+        let start = -((self.brush_size as i32 - 1) / 2);
+        let end = start + self.brush_size as i32 - 1;
+        for dx in start..=end {
+            for dy in start..=end {
+                if let Some((real_x, real_y)) = self.neighbour_position(x as i32 + dx, y as i32 + dy) {
+                    self.walls[real_x][real_y] = !self.walls[real_x][real_y];
+                    self.board.cells[real_x][real_y] = DEAD;
+                }
+            }
+        }
+    }
+
+    /// Removes every wall. Cells beside them see fewer neighbours, so the
+    /// next update has to look at the whole board again.
+    fn clear_walls(&mut self) {
+        self.walls = vec![vec![false; self.board.size.1]; self.board.size.0];
+        self.reset_period_detection();
+        self.all_dirty = true;
+        self.notice = Some("walls cleared".to_string());
+    }
+
+    fn increase_brush_size(&mut self) {
+        self.brush_size = (self.brush_size + 1).min(App::MAX_BRUSH_SIZE);
+    }
+
+    fn decrease_brush_size(&mut self) {
+        self.brush_size = self.brush_size.saturating_sub(1).max(1);
+    }
+
+    fn toggle_selection_start(&mut self) {
+        self.selection_start = match self.selection_start {
+            Some(_) => None,
+            None => Some(self.game_table_user_cursor),
+        };
+    }
+
+    /// Inclusive `(top_left, bottom_right)` corners of the rectangle between
+    /// the selection anchor and the cursor, whichever way round they are.
+    fn selection_rect(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (x1, y1) = self.selection_start?;
+        let (x2, y2) = self.game_table_user_cursor;
+        Some(((x1.min(x2), y1.min(y2)), (x1.max(x2), y1.max(y2))))
+    }
+
+    fn is_inside_selection(&self, x: usize, y: usize) -> bool {
+        self.selection_rect().is_some_and(|((x_min, y_min), (x_max, y_max))| {
+            (x_min..=x_max).contains(&x) && (y_min..=y_max).contains(&y)
+        })
+    }
+
+    fn fill_selection(&mut self, alive: bool) {
+        if !self.is_editing() {
+            return;
+        }
+        let Some(((x_min, y_min), (x_max, y_max))) = self.selection_rect() else {
+            return;
+        };
+        self.record_edit();
+
+        for row in &mut self.board.cells[x_min..=x_max] {
+            row[y_min..=y_max].fill(if alive { ALIVE } else { DEAD });
+        }
+    }
+
+    /// Fills the selection with a random soup at `fill_density`, drawn from
+    /// `rng`. Without a selection the whole board is filled. Either way it
+    /// is a normal edit: `<u>` undoes it and the seed stays the same.
+    fn fill_selection_randomly(&mut self) {
+        if !self.is_editing() {
+            return;
+        }
+        let ((x_min, y_min), (x_max, y_max)) = self
+            .selection_rect()
+            .unwrap_or(((0, 0), (self.board.size.0 - 1, self.board.size.1 - 1)));
+        self.record_edit();
+
+        for row in &mut self.board.cells[x_min..=x_max] {
+            for cell in &mut row[y_min..=y_max] {
+                *cell = if self.rng.random_bool(self.fill_density) { ALIVE } else { DEAD };
+            }
+        }
+    }
+
+    fn copy_selection(&mut self) {
+        let Some(((x_min, y_min), (x_max, y_max))) = self.selection_rect() else {
+            self.notice = Some("set an anchor with <z> to copy".to_string());
+            return;
+        };
+
+        let clipboard = self.board.cells[x_min..=x_max]
+            .iter()
+            .map(|row| row[y_min..=y_max].to_vec())
+            .collect();
+        self.clipboard = Some(clipboard);
+    }
+
+    fn rotate_clipboard(&mut self) {
+        match &mut self.clipboard {
+            Some(clipboard) => *clipboard = rotate_table(clipboard),
+            None => self.notice = Some("copy a selection with <c> first".to_string()),
+        }
+    }
+
+    /// Mirrors the clipboard left to right, or top to bottom when
+    /// `horizontal` is false.
+    fn flip_clipboard(&mut self, horizontal: bool) {
+        match &mut self.clipboard {
+            Some(clipboard) if horizontal => clipboard.iter_mut().for_each(|row| row.reverse()),
+            Some(clipboard) => clipboard.reverse(),
+            None => self.notice = Some("copy a selection with <c> first".to_string()),
+        }
+    }
+
+    /// Stamps the clipboard with its top-left corner on the cursor, OR-ing it
+    /// onto the board, or replacing the covered cells when `overwrite` is set.
+    /// What would fall past the grid edge is dropped, with a notice.
+    fn paste_clipboard(&mut self, overwrite: bool) {
+        if !self.is_editing() {
+            return;
+        }
+        let Some(clipboard) = self.clipboard.clone() else {
+            return;
+        };
+        self.record_edit();
+
+        let (x, y) = self.game_table_user_cursor;
+        let (height, width) = self.board.size;
+        for (row, clipboard_row) in self.board.cells[x..].iter_mut().zip(&clipboard) {
+            for (cell, clipboard_cell) in row[y..].iter_mut().zip(clipboard_row) {
+                if overwrite || *clipboard_cell != DEAD {
+                    *cell = *clipboard_cell;
+                }
+            }
+        }
+
+        let clipboard_height = clipboard.len();
+        let clipboard_width = clipboard.first().map_or(0, Vec::len);
+        if x + clipboard_height > height || y + clipboard_width > width {
+            self.notice = Some("paste clipped at the grid edge".to_string());
+        }
+    }
+
+    /// Flips every live cell to dead and every dead one to alive. Walls and
+    /// the dying cells of a Generations rule are left as they are, so
+    /// inverting twice gives back the same board.
+    fn invert_board(&mut self) {
+        self.record_edit();
+        for (cells, walls) in self.board.cells.iter_mut().zip(&self.walls) {
+            for (cell, wall) in cells.iter_mut().zip(walls) {
+                match *cell {
+                    _ if *wall => {}
+                    ALIVE => *cell = DEAD,
+                    DEAD => *cell = ALIVE,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn toggle_cell(&mut self, x: usize, y: usize) {
+        self.board.cells[x][y] = if self.board.cells[x][y] == ALIVE { DEAD } else { ALIVE };
+    }
+
+    fn increase_update_per_second_max(&mut self, update_per_second: f64) {
+        self.update_per_second_max = (self.update_per_second_max + update_per_second).min(App::MAX_UPDATE_PER_SECOND);
+    }
+
+    fn decrease_update_per_second_max(&mut self, update_per_second: f64) {
+        self.update_per_second_max = (self.update_per_second_max - update_per_second).max(App::MIN_UPDATE_PER_SECOND);
+    }
+
+    fn scale_update_per_second_max(&mut self, factor: f64) {
+        self.update_per_second_max =
+            (self.update_per_second_max * factor).clamp(App::MIN_UPDATE_PER_SECOND, App::MAX_UPDATE_PER_SECOND);
+    }
+
+    /// Moves the rate along the `<U>` speed ramp by one frame lasting
+    /// `elapsed`: up toward `RAMP_MAX_UPDATE_PER_SECOND` while the key is
+    /// held, then back down to the default, where the ramp ends. Key
+    /// repeats arrive as separate presses, so the key counts as held until
+    /// `RAMP_HOLD` has passed without one.
+    fn ramp_update_per_second_max(&mut self, now: Instant, elapsed: Duration) {
+        let Some(pressed_at) = self.ramp_pressed_at else {
+            return;
+        };
+        let factor = App::RAMP_FACTOR_PER_SECOND.powf(elapsed.as_secs_f64());
+        if now - pressed_at < App::RAMP_HOLD {
+            let cap = App::RAMP_MAX_UPDATE_PER_SECOND.max(self.update_per_second_max);
+            self.update_per_second_max = (self.update_per_second_max * factor).min(cap);
+        } else if self.update_per_second_max > App::DEFAULT_MAX_UPDATE_PER_SECOND {
+            self.update_per_second_max = (self.update_per_second_max / factor).max(App::DEFAULT_MAX_UPDATE_PER_SECOND);
+        } else {
+            self.update_per_second_max = (self.update_per_second_max * factor).min(App::DEFAULT_MAX_UPDATE_PER_SECOND);
+        }
+        if self.update_per_second_max == App::DEFAULT_MAX_UPDATE_PER_SECOND && now - pressed_at >= App::RAMP_HOLD {
+            self.ramp_pressed_at = None;
+        }
+    }
+
+    /// Asks `run` for `count` generations, pausing first if needed.
+    fn toggle_step_by_step(&mut self, count: u64) {
+        self.step_by_step_next = true;
+        self.step_by_step_count = count;
+        if !self.game_pause {
+            self.game_pause = true;
+        }
+    }
+
+    fn reset_update_per_second_max(&mut self) {
+        self.update_per_second_max = App::DEFAULT_MAX_UPDATE_PER_SECOND;
+    }
+
+    fn reset_game_table(&mut self) {
+        self.update_times.clear();
+        if self.game_table_user_cursor != self.game_table_user_cursor2 {
+            self.record_edit();
+            let (selected_table, (x, y)) = self.get_selected_table();
+            for row in x..x + selected_table.len() {
+                for cell in y..y + selected_table[0].len() {
+                    self.board.cells[row][cell] = DEAD;
+                }
+            }
+        } else {
+            self.board = Board::empty(self.board.size);
+            self.reset_board_tracking();
+            self.clear_history();
+            if self.walls.iter().flatten().any(|wall| *wall) {
+                self.clear_walls_armed = true;
+                self.notice = Some("walls kept, press <n> again to clear them".to_string());
+            }
+        }
+    }
+
+    /// To be called before any user edit of the board: makes it undoable and
+    /// restarts period detection, since the old boards no longer apply.
+    fn record_edit(&mut self) {
+        self.push_history_entry();
+        self.reset_period_detection();
+        self.all_dirty = true;
+    }
+
+    /// Remembers the current board so the next change can be undone. Only
+    /// the last `App::HISTORY_LENGTH` boards are kept. The board is copied
+    /// into the entry it evicts, or into a dropped redo entry, so a full
+    /// history costs no allocation per step.
+    fn push_history_entry(&mut self) {
+        let spare = if self.history.len() == App::HISTORY_LENGTH {
+            self.history.pop_front()
+        } else {
+            self.redo_history.pop()
+        };
+        let cells = match spare {
+            Some((mut cells, _)) => {
+                cells.clone_from(&self.board.cells);
+                cells
+            }
+            None => self.board.cells.clone(),
+        };
+        self.history.push_back((cells, self.generation));
+        self.redo_history.clear();
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+        self.redo_history.clear();
+    }
+
+    fn undo(&mut self) {
+        let Some((game_table, generation)) = self.history.pop_back() else {
+            return;
+        };
+
+        self.game_pause = true;
+        let current = std::mem::replace(&mut self.board.cells, game_table);
+        self.redo_history.push((current, self.generation));
+        self.generation = generation;
+        self.reset_period_detection();
+        self.all_dirty = true;
+    }
+
+    /// Goes back one generation, when the last history entry is the board
+    /// `step` saw just before the current one. After an edit, or once the
+    /// history runs out, this does nothing: `<u>` still undoes the edit.
+    fn step_backward(&mut self) {
+        if self.history.back().is_none_or(|(_, generation)| generation + 1 != self.generation) {
+            self.notice = Some("no earlier generation in history".to_string());
+            return;
+        }
+
+        self.undo();
+        self.population_history.pop_back();
+    }
+
+    fn redo(&mut self) {
+        let Some((game_table, generation)) = self.redo_history.pop() else {
+            return;
+        };
+
+        let current = std::mem::replace(&mut self.board.cells, game_table);
+        self.history.push_back((current, self.generation));
+        self.generation = generation;
+        self.reset_period_detection();
+        self.all_dirty = true;
+    }
+
+    fn get_selected_table(&self) -> (GameTable, (usize, usize)) {
+        let (x1, y1) = self.game_table_user_cursor;
+        let (x2, y2) = self.game_table_user_cursor2;
+        let x_min = x1.min(x2);
+        let x_max = x1.max(x2);
+        let y_min = y1.min(y2);
+        let y_max = y1.max(y2);
+
+        let mut selected_table = Vec::with_capacity(x_max - x_min + 1);
+
+        for x in x_min..=x_max {
+            let mut row = Vec::with_capacity(y_max - y_min + 1);
+            for y in y_min..=y_max {
+                row.push(self.board.cells[x][y]);
+            }
+            selected_table.push(row);
+        }
+        (selected_table, (x_min, y_min))
+    }
+
+    fn is_inside_user_cursor(&self, x: usize, y: usize) -> bool {
+        let (x1, y1) = self.game_table_user_cursor;
+        let (x2, y2) = self.game_table_user_cursor2;
+        let x_min = x1.min(x2);
+        let x_max = x1.max(x2);
+        let y_min = y1.min(y2);
+        let y_max = y1.max(y2);
+        x >= x_min && x <= x_max && y >= y_min && y <= y_max
+    }
+
+    fn save_selected_table(&self) {
+        if !self.game_pause {
+            return;
+        }
+
+        let (selected_table, _) = self.get_selected_table();
+
+        let mut bool_buffer: Vec<bool> = Vec::with_capacity(8);
+        let mut buffer_i = 0;
+        let mut row_file:Vec<u8> = Vec::new();
+
+        row_file.push(selected_table.len() as u8);
+        row_file.push(selected_table[0].len() as u8);
+        for row in selected_table {
+            for cell in row {
+                bool_buffer.push(cell == ALIVE);
+                buffer_i += 1;
+                if buffer_i == 8 {
+                    row_file.push(bool_8_to_u8(&bool_buffer));
+                    buffer_i = 0;
+                    bool_buffer.clear();
+                }
+            }
+        }
+        if buffer_i != 0 {
+            row_file.push(bool_8_to_u8(&bool_buffer));
+        }
+
+        std::fs::write("save.data", row_file).unwrap();
+    }
+
+    fn import_selected_table(&mut self) {
+        if !self.game_pause {
+            return;
+        }
+        self.record_edit();
+
+        let vec = std::fs::read("save.data").unwrap();
+        let length = vec[0] as usize;
+        let height = vec[1] as usize;
+
+        let mut bool_buffer: Vec<bool> = Vec::new();
+
+        for byte in &vec[2..] {
+            bool_buffer.extend(u8_to_bool_8(*byte));
+        }
+        let (x, y) = self.game_table_user_cursor;
+
+        let mut i = 0;
+        for row in x..(x + length) {
+            for cell in y..(y + height) {
+                self.board.cells[row][cell] = if bool_buffer[i] { ALIVE } else { DEAD };
+                i += 1;
+            }
+        }
+    }
+
+    fn save_rle(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, formats::rle_encode(&self.board.cells, &self.rule.to_string()))
+    }
+
+    fn load_rle(&mut self, path: &Path) -> io::Result<()> {
+        let pattern = formats::rle_decode(&std::fs::read_to_string(path)?)?;
+        self.place_loaded_pattern(&pattern);
+        Ok(())
+    }
+
+    /// Replaces the board with `pattern`, centered when it is smaller than
+    /// the grid and clamped to the grid (with a notice) when it is larger.
+    fn place_loaded_pattern(&mut self, pattern: &GameTable) {
+        let (height, width) = self.board.size;
+        let pattern_height = pattern.len();
+        let pattern_width = pattern.first().map_or(0, Vec::len);
+        let offset_x = height.saturating_sub(pattern_height) / 2;
+        let offset_y = width.saturating_sub(pattern_width) / 2;
+
+        self.record_edit();
+        self.board = Board::empty(self.board.size);
+        self.reset_board_tracking();
+        for (x, row) in pattern.iter().take(height).enumerate() {
+            for (y, cell) in row.iter().take(width).enumerate() {
+                self.board.cells[offset_x + x][offset_y + y] = *cell;
+            }
+        }
+
+        self.notice = if pattern_height > height || pattern_width > width {
+            Some(format!("pattern {pattern_width}x{pattern_height} clamped to grid {width}x{height}"))
+        } else {
+            None
+        };
+    }
+
+    fn load_cells(&mut self, path: &Path) -> io::Result<()> {
+        let pattern = formats::parse_cells(&std::fs::read_to_string(path)?)?;
+        self.stamp_pattern(&pattern, self.game_table_user_cursor);
+        Ok(())
+    }
+
+    /// Clears the board and runs the Gosper glider gun under Conway's rule.
+    /// The gun sits `DEMO_OFFSET` from the top-left corner, so its gliders
+    /// cross the whole board, and the boundary is dead so they don't wrap
+    /// back into it. Unlike a full `<n>` reset the history is kept, so `<u>`
+    /// brings the old board back.
+    fn launch_glider_gun_demo(&mut self) {
+        let gun_height = GOSPER_GLIDER_GUN.cells.len();
+        let gun_width = GOSPER_GLIDER_GUN.cells.iter().map(|row| row.len()).max().unwrap_or(0);
+        let (height, width) = self.board.size;
+        if height < gun_height + 2 * App::DEMO_OFFSET.0 || width < gun_width + 2 * App::DEMO_OFFSET.1 {
+            self.notice = Some(format!(
+                "the glider gun needs a {}x{} grid",
+                gun_width + 2 * App::DEMO_OFFSET.1,
+                gun_height + 2 * App::DEMO_OFFSET.0
+            ));
+            return;
+        }
+
+        self.record_edit();
+        self.update_times.clear();
+        self.board = Board::empty(self.board.size);
+        self.reset_board_tracking();
+        self.rule = Rule::CONWAY;
+        self.boundary = Boundary::Dead;
+        self.write_pattern(&Orientation::default().apply(GOSPER_GLIDER_GUN.cells), App::DEMO_OFFSET);
+        self.update_per_second_max = App::DEMO_UPDATE_PER_SECOND;
+        self.game_pause = false;
+    }
+
+    fn place_pattern(&mut self, pattern: &Pattern, at: (usize, usize), orientation: Orientation) {
+        self.stamp_pattern(&orientation.apply(pattern.cells), at);
+    }
+
+    /// Writes `pattern` with its top-left corner at `at`, wrapping around
+    /// the edges of the board.
+    fn stamp_pattern<R: AsRef<[Cell]>>(&mut self, pattern: &[R], at: (usize, usize)) {
+        self.record_edit();
+        self.write_pattern(pattern, at);
+    }
+
+    /// `stamp_pattern` without the history entry, for callers that already
+    /// recorded the edit.
+    fn write_pattern<R: AsRef<[Cell]>>(&mut self, pattern: &[R], at: (usize, usize)) {
+        let (height, width) = self.board.size;
+        for (x, row) in pattern.iter().enumerate() {
+            for (y, cell) in row.as_ref().iter().enumerate() {
+                self.board.cells[(at.0 + x) % height][(at.1 + y) % width] = *cell;
+            }
+        }
+    }
+
+    fn save_slot(&mut self, slot: usize) {
+        self.slots[slot] = Some(self.board.cells.clone());
+        self.notice = Some(format!("saved to slot {}", slot + 1));
+    }
+
+    /// Pauses and puts the board saved in `slot` back, resized to the grid
+    /// if the grid changed since. An empty slot leaves the board alone.
+    fn restore_slot(&mut self, slot: usize) {
+        let Some(game_table) = &self.slots[slot] else {
+            self.notice = Some(format!("slot {} is empty", slot + 1));
+            return;
+        };
+
+        let game_table = resize_table(game_table, self.board.size);
+        self.record_edit();
+        self.game_pause = true;
+        self.board.cells = game_table;
+        self.notice = Some(format!("restored slot {}", slot + 1));
+    }
+
+    fn save_session(&self, path: &Path) -> io::Result<()> {
+        let session = Session {
+            cells: self.board.cells.clone(),
+            generation: self.generation,
+            rule: Some(self.rule.to_string()),
+            boundary: Some(self.boundary.name().to_string()),
+            range: Some(self.range),
+            update_per_second_max: Some(self.update_per_second_max),
+            theme: Some(self.theme.name.to_string()),
+            cursor: Some(self.game_table_user_cursor),
+        };
+        formats::write_atomically(path, &serde_json::to_string(&session)?)
+    }
+
+    /// Restores a session saved by `save_session`. Every value is checked
+    /// before any is applied, so a bad file leaves the game untouched. The
+    /// board keeps its top-left corner and is cut or padded to the size
+    /// the terminal gives the grid. It can't be undone.
+    fn load_session(&mut self, path: &Path) -> io::Result<()> {
+        let invalid = |reason: String| io::Error::new(io::ErrorKind::InvalidData, reason);
+        let session: Session = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let session_width = session.cells.first().map_or(0, Vec::len);
+        if session_width == 0 || session.cells.iter().any(|row| row.len() != session_width) {
+            return Err(invalid("the session board must be a non-empty rectangle".to_string()));
+        }
+        let rule = session.rule.as_deref().map(Rule::parse).transpose().map_err(invalid)?.unwrap_or(self.rule);
+        if session.cells.iter().flatten().any(|cell| *cell >= rule.states) {
+            return Err(invalid(format!("the session board has states the rule {rule} lacks")));
+        }
+        let boundary = match session.boundary {
+            Some(name) => [Boundary::Toroidal, Boundary::Dead]
+                .into_iter()
+                .find(|boundary| boundary.name() == name)
+                .ok_or_else(|| invalid(format!("unknown boundary '{name}'")))?,
+            None => self.boundary,
+        };
+        let theme = match session.theme {
+            Some(name) => *Theme::PRESETS
+                .iter()
+                .find(|theme| theme.name == name)
+                .ok_or_else(|| invalid(format!("unknown theme '{name}'")))?,
+            None => self.theme,
+        };
+
+        let (height, width) = self.world_size();
+        let size = (height.max(App::MIN_GRID_SIZE), width.max(App::MIN_GRID_SIZE));
+        self.board = Board { cells: resize_table(&session.cells, size), size };
+        self.walls = vec![vec![false; size.1]; size.0];
+        // Undo would bring back boards of another size, as after a resize.
+        self.clear_history();
+        self.reset_board_tracking();
+        self.generation = session.generation;
+        self.rule = rule;
+        self.boundary = boundary;
+        self.range = session.range.unwrap_or(self.range).clamp(1, MAX_RANGE);
+        self.update_per_second_max = session
+            .update_per_second_max
+            .unwrap_or(self.update_per_second_max)
+            .clamp(App::MIN_UPDATE_PER_SECOND, App::MAX_UPDATE_PER_SECOND);
+        self.theme = theme;
+        let (x, y) = session.cursor.unwrap_or(self.game_table_user_cursor);
+        self.game_table_user_cursor = (x.min(size.0.saturating_sub(1)), y.min(size.1.saturating_sub(1)));
+        self.game_table_user_cursor2 = self.game_table_user_cursor;
+        self.clamp_viewport_origin();
+
+        self.notice = Some(if session.cells.len() > size.0 || session_width > size.1 {
+            format!("session board {session_width}x{} cut to grid {}x{}", session.cells.len(), size.1, size.0)
+        } else {
+            format!("loaded {}", path.display())
+        });
+        Ok(())
+    }
+
+    fn save_session_to_default_file(&mut self) {
+        self.notice = match self.save_session(Path::new(App::SESSION_FILE)) {
+            Ok(()) => Some(format!("saved to {}", App::SESSION_FILE)),
+            Err(error) => Some(error.to_string()),
+        };
+    }
+
+    fn load_session_from_default_file(&mut self) {
+        if let Err(error) = self.load_session(Path::new(App::SESSION_FILE)) {
+            self.notice = Some(error.to_string());
+        }
+    }
+
+    fn save_rle_to_default_file(&mut self) {
+        self.notice = match self.save_rle(Path::new(App::RLE_FILE)) {
+            Ok(()) => Some(format!("saved to {}", App::RLE_FILE)),
+            Err(error) => Some(error.to_string()),
+        };
+    }
+
+    #[cfg(feature = "image")]
+    fn board_image(&self, scale: u32) -> image::RgbImage {
+        table_image(&self.board.cells, &self.walls, scale)
+    }
+
+    #[cfg(feature = "image")]
+    fn export_png(&self, path: &Path) -> io::Result<()> {
+        self.board_image(self.png_scale)
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(io::Error::other)
+    }
+
+    fn export_png_to_timestamped_file(&mut self) {
+        #[cfg(feature = "image")]
+        {
+            let path = format!("board-{}.png", unix_timestamp());
+            self.notice = match self.export_png(Path::new(&path)) {
+                Ok(()) => Some(format!("exported to {path}")),
+                Err(error) => Some(error.to_string()),
+            };
+        }
+        #[cfg(not(feature = "image"))]
+        {
+            self.notice = Some("built without the image feature".to_string());
+        }
+    }
+
+    /// Starts recording, or stops and writes what was recorded to
+    /// `recording-<unix seconds>.gif`.
+    fn toggle_recording(&mut self) {
+        if !cfg!(feature = "image") {
+            self.notice = Some("built without the image feature".to_string());
+            return;
+        }
+        let Some(frames) = self.recording.take() else {
+            self.recording = Some(vec![self.board.cells.clone()]);
+            self.notice = Some("recording, <D> to stop".to_string());
+            return;
+        };
+
+        let path = format!("recording-{}.gif", unix_timestamp());
+        self.notice = match self.write_gif(Path::new(&path), &frames) {
+            Ok(()) => Some(format!("{} frames saved to {path}", frames.len())),
+            Err(error) => Some(error.to_string()),
+        };
+    }
+
+    /// Appends the current board to the recording, up to
+    /// `MAX_RECORDED_FRAMES`.
+    fn record_frame(&mut self) {
+        let Some(frames) = &mut self.recording else {
+            return;
+        };
+        if frames.len() < App::MAX_RECORDED_FRAMES {
+            frames.push(self.board.cells.clone());
+        } else {
+            self.notice = Some(format!("recording full at {} frames, <D> to save", App::MAX_RECORDED_FRAMES));
+        }
+    }
+
+    /// Writes `frames` as a looping GIF, one frame per generation at the
+    /// maximum update rate.
+    #[cfg(feature = "image")]
+    fn write_gif(&self, path: &Path, frames: &[GameTable]) -> io::Result<()> {
+        use image::codecs::gif::{GifEncoder, Repeat};
+        use image::{Delay, DynamicImage, Frame};
+
+        let delay = Delay::from_saturating_duration(Duration::from_secs_f64(1.0 / self.update_per_second_max));
+        let mut encoder = GifEncoder::new(io::BufWriter::new(std::fs::File::create(path)?));
+        encoder.set_repeat(Repeat::Infinite).map_err(io::Error::other)?;
+        let frames = frames.iter().map(|cells| {
+            let image = DynamicImage::ImageRgb8(table_image(cells, &self.walls, App::GIF_SCALE));
+            Frame::from_parts(image.into_rgba8(), 0, 0, delay)
+        });
+        encoder.encode_frames(frames).map_err(io::Error::other)
+    }
+
+    #[cfg(not(feature = "image"))]
+    fn write_gif(&self, _path: &Path, _frames: &[GameTable]) -> io::Result<()> {
+        Err(io::Error::other("built without the image feature"))
+    }
+
+    /// Copies the live part of the board to the system clipboard as `#`/`.`
+    /// text. Without clipboard access (or the `clipboard` feature) it goes
+    /// to `CLIPBOARD_FALLBACK_FILE` as a `.cells` pattern instead.
+    fn export_to_system_clipboard(&mut self) {
+        let Some(text) = formats::plaintext_encode(&self.board.cells, '#', '.') else {
+            self.notice = Some("the board is empty, nothing to copy".to_string());
+            return;
+        };
+
+        self.notice = match copy_to_system_clipboard(text) {
+            Ok(()) => Some("board copied to the clipboard".to_string()),
+            Err(error) => {
+                let cells = formats::plaintext_encode(&self.board.cells, 'O', '.').unwrap_or_default();
+                match std::fs::write(App::CLIPBOARD_FALLBACK_FILE, cells) {
+                    Ok(()) => Some(format!("{error}, saved to {} instead", App::CLIPBOARD_FALLBACK_FILE)),
+                    Err(write_error) => Some(format!("{error}, and {write_error}")),
+                }
+            }
+        };
+    }
+
+    /// Writes the board to `AUTOSAVE_FILE` through a temporary file, so a
+    /// crash mid-write never leaves a truncated autosave behind.
+    fn autosave(&mut self) {
+        let rle = formats::rle_encode(&self.board.cells, &self.rule.to_string());
+        if let Err(error) = formats::write_atomically(Path::new(App::AUTOSAVE_FILE), &rle) {
+            self.notice = Some(format!("autosave failed: {error}"));
+        }
+    }
+
+    fn load_rle_from_default_file(&mut self) {
+        if let Err(error) = self.load_rle(Path::new(App::RLE_FILE)) {
+            self.notice = Some(error.to_string());
+        }
+    }
+
+    fn load_cells_from_default_file(&mut self) {
+        if let Err(error) = self.load_cells(Path::new(App::CELLS_FILE)) {
+            self.notice = Some(error.to_string());
+        }
+    }
+
+    /// Cells that fit on screen between the instruction and information lines.
+    fn viewport_size(&self) -> (usize, usize) {
+        let (height, width) = self.terminal_size;
+        let status_rows = App::STATUS_ROWS - usize::from(!self.show_status);
+        (height.saturating_sub(status_rows), width / self.cell_width())
+    }
+
+    /// Whether the terminal leaves less than a `MIN_GRID_SIZE` square of
+    /// cells between the status lines.
+    fn terminal_too_small(&self) -> bool {
+        let (height, width) = self.viewport_size();
+        height < App::MIN_GRID_SIZE || width < App::MIN_GRID_SIZE
+    }
+
+    /// Terminal columns per cell: two with aspect correction, so cells come
+    /// out about square.
+    fn cell_width(&self) -> usize {
+        if self.aspect_correct { 2 } else { 1 }
+    }
+
+    /// Hides the information line, giving its row to the board, or brings
+    /// it back, shrinking the board by that row again.
+    fn toggle_status(&mut self) {
+        self.show_status = !self.show_status;
+        self.resize_grid(self.world_size());
+    }
+
+    /// Halves (or restores) the horizontal resolution, resizing the grid to
+    /// the new viewport the way a terminal resize does.
+    fn toggle_aspect_correct(&mut self) {
+        self.aspect_correct = !self.aspect_correct;
+        self.resize_grid(self.world_size());
+    }
+
+    /// Replaces the board with a random soup from the next seed.
+    fn regenerate_game_table(&mut self) {
+        self.record_edit();
+        self.seed = self.seed.wrapping_add(1);
+        self.board = Board::random(self.board.size, self.seed, self.fill_density);
+        self.rng = StdRng::seed_from_u64(self.seed);
+        self.reset_board_tracking();
+    }
+
+    /// Grid size from the command line where given, the viewport otherwise,
+    /// squared down when the square world is on.
+    fn world_size(&self) -> (usize, usize) {
+        let (view_height, view_width) = self.viewport_size();
+        let height = self.requested_grid_size.0.unwrap_or(view_height);
+        let width = self.requested_grid_size.1.unwrap_or(view_width);
+        if self.square_world {
+            let side = height.min(width);
+            (side, side)
+        } else {
+            (height, width)
+        }
+    }
+
+    /// Resizes the grid the way a terminal resize does, keeping the cells
+    /// that still fit.
+    fn toggle_square_world(&mut self) {
+        self.square_world = !self.square_world;
+        self.resize_grid(self.world_size());
+    }
+
+    /// Reallocates the grid to `new_size`, keeping the overlapping top-left
+    /// region and filling any new area with dead cells.
+    fn resize_grid(&mut self, new_size: (usize, usize)) {
+        let new_size = (
+            new_size.0.clamp(App::MIN_GRID_SIZE, App::MAX_GRID_SIZE),
+            new_size.1.clamp(App::MIN_GRID_SIZE, App::MAX_GRID_SIZE),
+        );
+        self.clear_history();
+        self.update_times.clear();
+        self.board.cells = resize_table(&self.board.cells, new_size);
+        self.ever_changed = resize_table(&self.ever_changed, new_size);
+        self.just_died = resize_table(&self.just_died, new_size);
+        self.walls = resize_table(&self.walls, new_size);
+        self.cell_age = resize_table(&self.cell_age, new_size);
+        self.board.size = new_size;
+        self.all_dirty = true;
+
+        let clamp = |(x, y): (usize, usize)| (x.min(new_size.0 - 1), y.min(new_size.1 - 1));
+        self.game_table_user_cursor = clamp(self.game_table_user_cursor);
+        self.game_table_user_cursor2 = clamp(self.game_table_user_cursor2);
+        self.selection_start = self.selection_start.map(clamp);
+        self.clamp_viewport_origin();
+    }
+
+    fn grow_grid(&mut self) {
+        let (height, width) = self.board.size;
+        self.resize_grid((height + App::GRID_RESIZE_STEP, width + App::GRID_RESIZE_STEP));
+        self.requested_grid_size = (Some(self.board.size.0), Some(self.board.size.1));
+    }
+
+    fn shrink_grid(&mut self) {
+        let (height, width) = self.board.size;
+        self.resize_grid((height.saturating_sub(App::GRID_RESIZE_STEP), width.saturating_sub(App::GRID_RESIZE_STEP)));
+        self.requested_grid_size = (Some(self.board.size.0), Some(self.board.size.1));
+    }
+
+    /// Moves the viewport by `dx` rows and `dy` columns, without moving the
+    /// edit cursor.
+    fn pan(&mut self, dx: isize, dy: isize) {
+        self.viewport_origin.0 = self.viewport_origin.0.saturating_add_signed(dx);
+        self.viewport_origin.1 = self.viewport_origin.1.saturating_add_signed(dy);
+        self.clamp_viewport_origin();
+    }
+
+    /// Pans the viewport so `cursor` stays `VIEWPORT_MARGIN` cells away from
+    /// its edges. A cursor wrapping around the grid takes the viewport to
+    /// the other side with it.
+    fn follow_cursor(&mut self, cursor: (usize, usize)) {
+        let (view_height, view_width) = self.viewport_size();
+        let follow = |origin: usize, cursor: usize, view: usize| {
+            let margin = App::VIEWPORT_MARGIN.min(view.saturating_sub(1) / 2);
+            if cursor < origin + margin {
+                cursor.saturating_sub(margin)
+            } else if cursor + margin >= origin + view {
+                cursor + margin + 1 - view
+            } else {
+                origin
+            }
+        };
+        self.viewport_origin = (
+            follow(self.viewport_origin.0, cursor.0, view_height),
+            follow(self.viewport_origin.1, cursor.1, view_width),
+        );
+        self.clamp_viewport_origin();
+    }
+
+    /// Pans the viewport so the live cells' bounding box is centered, or,
+    /// when the box is larger than the viewport, their centroid. An empty
+    /// board brings the viewport back to the origin.
+    fn fit_view_to_pattern(&mut self) {
+        let Some(((x_min, y_min), (x_max, y_max))) = formats::bounding_box(&self.board.cells) else {
+            self.viewport_origin = (0, 0);
+            return;
+        };
+        let (view_height, view_width) = self.viewport_size();
+        let center = if x_max - x_min < view_height && y_max - y_min < view_width {
+            ((x_min + x_max) / 2, (y_min + y_max) / 2)
+        } else {
+            let cells: Vec<(usize, usize)> = (0..self.board.size.0)
+                .flat_map(|x| (0..self.board.size.1).map(move |y| (x, y)))
+                .filter(|(x, y)| self.board.cells[*x][*y] == ALIVE)
+                .collect();
+            let (sum_x, sum_y) = cells.iter().fold((0, 0), |(sum_x, sum_y), (x, y)| (sum_x + x, sum_y + y));
+            (sum_x / cells.len(), sum_y / cells.len())
+        };
+        self.viewport_origin = (center.0.saturating_sub(view_height / 2), center.1.saturating_sub(view_width / 2));
+        self.clamp_viewport_origin();
+    }
+
+    /// Keeps the viewport inside the grid, so it never shows past its edge.
+    fn clamp_viewport_origin(&mut self) {
+        let (view_height, view_width) = self.viewport_size();
+        let max_x = self.board.size.0.saturating_sub(view_height);
+        let max_y = self.board.size.1.saturating_sub(view_width);
+        self.viewport_origin = (self.viewport_origin.0.min(max_x), self.viewport_origin.1.min(max_y));
+    }
+
+    fn load_kernel(&mut self) {
+        match std::fs::read_to_string(Kernel::FILE).and_then(|input| Kernel::parse(&input)) {
+            Ok(kernel) => {
+                self.kernel = Some(kernel);
+                self.notice = Some(format!("kernel loaded from {}", Kernel::FILE));
+            }
+            Err(error) => self.notice = Some(error.to_string()),
+        }
+    }
+
+    fn clear_kernel(&mut self) {
+        self.kernel = None;
+        self.notice = None;
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+}
+
+impl Widget for &App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let game_table_printed = self.print_game_table(area);
+        Paragraph::new(game_table_printed)
+            .render(area, buf)
+    }
+}
+
+/// Hash of a board, for period detection.
+fn hash_game_table(game_table: &GameTable) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    game_table.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Heatmap color of a live cell: white when newborn, fading to blue as it
+/// reaches `AGE_FOR_FULL_COLOR` generations.
+fn age_color(age: u16) -> Color {
+    const AGE_FOR_FULL_COLOR: u16 = 50;
+    let fade = 255 - (255 * age.min(AGE_FOR_FULL_COLOR) as u32 / AGE_FOR_FULL_COLOR as u32) as u8;
+    Color::Rgb(fade, fade, 255)
+}
+
+/// Dying cells fade from light to dark gray as they get closer to dead.
+fn dying_color(cell: Cell, states: u8) -> Color {
+    let shade = 250 - (cell as u32 - 2) * (250 - 236) / (states as u32 - 2).max(1);
+    Color::Indexed(shade as u8)
+}
 
-            self.game_table[x][y] = !self.game_table[x][y];
+/// Zero-based index of a `'1'..='9'` key, for slots and speed presets.
+fn digit_index(digit: char) -> usize {
+    digit as usize - '1' as usize
+}
 
+fn resize_table<T: Copy + Default>(table: &[Vec<T>], size: (usize, usize)) -> Vec<Vec<T>> {
+    let mut resized = vec![vec![T::default(); size.1]; size.0];
+    for (resized_row, row) in resized.iter_mut().zip(table) {
+        for (resized_cell, cell) in resized_row.iter_mut().zip(row) {
+            *resized_cell = *cell;
         }
     }
 
-    fn increase_update_per_second_max(&mut self, update_per_second: u16) {
-        self.update_per_second_max += update_per_second;
+    resized
+}
+
+/// Draws a board with `scale` pixels per cell side: live cells black, dead
+/// ones white, dying ones gray and walls yellow. `walls` that no longer
+/// match the board size are ignored.
+#[cfg(feature = "image")]
+fn table_image(cells: &GameTable, walls: &[Vec<bool>], scale: u32) -> image::RgbImage {
+    let height = cells.len();
+    let width = cells.first().map_or(0, Vec::len);
+    image::RgbImage::from_fn(width as u32 * scale, height as u32 * scale, |column, row| {
+        let (x, y) = ((row / scale) as usize, (column / scale) as usize);
+        let wall = walls.get(x).and_then(|row| row.get(y)).copied().unwrap_or(false);
+        match cells[x][y] {
+            _ if wall => image::Rgb([230, 180, 0]),
+            ALIVE => image::Rgb([0, 0, 0]),
+            DEAD => image::Rgb([255, 255, 255]),
+            _ => image::Rgb([160, 160, 160]),
+        }
+    })
+}
+
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs())
+}
+
+#[cfg(feature = "clipboard")]
+fn copy_to_system_clipboard(text: String) -> Result<(), String> {
+    arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)).map_err(|error| error.to_string())
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_system_clipboard(_text: String) -> Result<(), String> {
+    Err("built without the clipboard feature".to_string())
+}
+
+/// Parses a `#RRGGBB` color, the `#` being optional.
+fn parse_hex_color(input: &str) -> Result<Color, String> {
+    let hex = input.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.chars().all(|character| character.is_ascii_hexdigit()) {
+        return Err(format!("invalid color '{input}', expected #RRGGBB"));
     }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+    Ok(Color::Rgb(channel(0), channel(2), channel(4)))
+}
 
-    fn decrease_update_per_second_max(&mut self, update_per_second: u16) {
-        if self.update_per_second_max - update_per_second > 0 {
-            self.update_per_second_max -= update_per_second;
+/// Cells of the Bresenham line from `from` to `to`, both included.
+fn line_cells(from: (usize, usize), to: (usize, usize)) -> Vec<(usize, usize)> {
+    let (mut x, mut y) = (from.0 as i64, from.1 as i64);
+    let (to_x, to_y) = (to.0 as i64, to.1 as i64);
+    let (dx, dy) = ((to_x - x).abs(), -(to_y - y).abs());
+    let (step_x, step_y) = ((to_x - x).signum(), (to_y - y).signum());
+    let mut error = dx + dy;
+    let mut cells = vec![(x as usize, y as usize)];
+    while (x, y) != (to_x, to_y) {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
         }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+        cells.push((x as usize, y as usize));
     }
+    cells
+}
 
-    fn toggle_step_by_step(&mut self) {
-        self.step_by_step_next = true;
-        if !self.game_pause {
-            self.game_pause = true;
+/// Rotates a table a quarter turn clockwise: a `h x w` table becomes
+/// `w x h`.
+fn rotate_table<T: Copy>(table: &[Vec<T>]) -> Vec<Vec<T>> {
+    let width = table.first().map_or(0, Vec::len);
+    (0..width).map(|y| table.iter().rev().map(|row| row[y]).collect()).collect()
+}
+
+/// Snaps to multiples of `App::PROBABILITY_STEP` so stepping back up lands
+/// exactly on 1.0 again.
+fn step_probability(probability: f64, delta: f64) -> f64 {
+    let steps = ((probability + delta) / App::PROBABILITY_STEP).round();
+    (steps * App::PROBABILITY_STEP).clamp(0.0, 1.0)
+}
+
+fn bool_8_to_u8(vec: &[bool]) -> u8 {
+    let mut u8_buffer: u8 = 0;
+    for (i, bit) in vec.iter().enumerate() {
+        u8_buffer |= u8::from(*bit) << (7 - i)
+    }
+
+    u8_buffer
+}
+
+fn u8_to_bool_8(u: u8) -> Vec<bool> {
+    let mut bool_buffer: Vec<bool> = Vec::with_capacity(8);
+    for i in 0..8 {
+        bool_buffer.push(u >> (7 - i) & 1 == 1);
+    }
+    bool_buffer
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrease_update_per_second_max_stays_at_minimum() {
+        let mut app = App {
+            update_per_second_max: 1.0,
+            ..App::default()
+        };
+        for _ in 0..5 {
+            app.decrease_update_per_second_max(1.0);
+            assert_eq!(app.update_per_second_max, App::MIN_UPDATE_PER_SECOND);
         }
+
+        app.decrease_update_per_second_max(f64::from(u16::MAX));
+        assert_eq!(app.update_per_second_max, App::MIN_UPDATE_PER_SECOND);
     }
 
-    fn reset_update_per_second_max(&mut self) {
-        self.update_per_second_max = App::DEFAULT_MAX_UPDATE_PER_SECOND;
+    #[test]
+    fn large_update_rate_steps_saturate() {
+        let mut app = App {
+            update_per_second_max: 15.0,
+            ..App::default()
+        };
+        app.decrease_update_per_second_max(App::UPDATE_PER_SECOND_LARGE_STEP);
+        assert_eq!(app.update_per_second_max, 5.0);
+        app.decrease_update_per_second_max(App::UPDATE_PER_SECOND_LARGE_STEP);
+        assert_eq!(app.update_per_second_max, App::MIN_UPDATE_PER_SECOND);
+
+        app.update_per_second_max = App::MAX_UPDATE_PER_SECOND - 5.0;
+        app.increase_update_per_second_max(App::UPDATE_PER_SECOND_LARGE_STEP);
+        assert_eq!(app.update_per_second_max, App::MAX_UPDATE_PER_SECOND);
     }
 
-    fn reset_game_table(&mut self) {
-        if self.game_table_user_cursor != self.game_table_user_cursor2 {
-            let (selected_table, (x, y)) = self.get_selected_table();
-            for row in x..x + selected_table.len() {
-                for cell in y..y + selected_table[0].len() {
-                    self.game_table[row][cell] = false;
+    #[test]
+    fn halving_the_update_rate_reaches_fractions() {
+        let mut app = App {
+            update_per_second_max: 1.0,
+            ..App::default()
+        };
+        app.scale_update_per_second_max(0.5);
+        assert_eq!(app.update_per_second_max, 0.5);
+        for _ in 0..5 {
+            app.scale_update_per_second_max(0.5);
+        }
+        assert_eq!(app.update_per_second_max, App::MIN_UPDATE_PER_SECOND);
+        app.scale_update_per_second_max(2.0);
+        assert_eq!(app.update_per_second_max, 0.5);
+    }
+
+    fn app_with_board(game_table: GameTable, rule: &str) -> App {
+        let board = Board::from_cells(game_table);
+        let size = board.size;
+        App {
+            board,
+            walls: vec![vec![false; size.1]; size.0],
+            ever_changed: vec![vec![false; size.1]; size.0],
+            just_died: vec![vec![false; size.1]; size.0],
+            cell_age: vec![vec![0; size.1]; size.0],
+            rule: Rule::parse(rule).unwrap(),
+            ..App::default()
+        }
+    }
+
+    /// Plain toroidal Life on booleans, to check the `Cell` update against.
+    fn reference_life_step(board: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        let (height, width) = (board.len(), board[0].len());
+        let mut next = vec![vec![false; width]; height];
+        for x in 0..height {
+            for y in 0..width {
+                let mut count = 0;
+                for dx in [height - 1, 0, 1] {
+                    for dy in [width - 1, 0, 1] {
+                        if (dx, dy) != (0, 0) && board[(x + dx) % height][(y + dy) % width] {
+                            count += 1;
+                        }
+                    }
                 }
+                next[x][y] = count == 3 || (count == 2 && board[x][y]);
             }
-        } else {
-            self.game_table = initialize_empty_game_table(self.game_table_size);
         }
+        next
     }
 
-    fn get_selected_table(&self) -> (Vec<Vec<bool>>, (usize, usize)) {
-        let (x1, y1) = self.game_table_user_cursor;
-        let (x2, y2) = self.game_table_user_cursor2;
-        let x_min = x1.min(x2);
-        let x_max = x1.max(x2);
-        let y_min = y1.min(y2);
-        let y_max = y1.max(y2);
+    #[test]
+    fn two_states_behave_like_classic_life() {
+        let soup = Board::random((20, 30), 7, 0.35).cells;
+        let mut expected: Vec<Vec<bool>> =
+            soup.iter().map(|row| row.iter().map(|cell| *cell == ALIVE).collect()).collect();
+        let mut app = app_with_board(soup, "B3/S23/C2");
 
-        let mut selected_table = Vec::with_capacity(x_max - x_min + 1);
+        for _ in 0..30 {
+            app.update_game_table();
+            expected = reference_life_step(&expected);
+            let board: Vec<Vec<bool>> =
+                app.board.cells.iter().map(|row| row.iter().map(|cell| *cell == ALIVE).collect()).collect();
+            assert_eq!(board, expected);
+            assert!(app.board.cells.iter().flatten().all(|cell| *cell <= ALIVE));
+        }
+    }
 
-        for x in x_min..=x_max {
-            let mut row = Vec::with_capacity(y_max - y_min + 1);
-            for y in y_min..=y_max {
-                row.push(self.game_table[x][y]);
+    #[test]
+    fn inverting_twice_restores_the_board() {
+        let mut app = app_with_board(Board::random((9, 11), 3, 0.4).cells, "B2/S/C3");
+        app.walls[4][5] = true;
+        app.board.cells[4][5] = DEAD;
+        app.board.cells[0][0] = 2;
+        let original = app.board.clone();
+
+        app.invert_board();
+        assert_eq!(app.population(), 9 * 11 - original.population() - 2);
+        assert_eq!(app.board.cells[0][0], 2);
+        app.invert_board();
+        assert_eq!(app.board, original);
+        app.undo();
+        app.undo();
+        assert_eq!(app.board, original);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_update_matches_serial() {
+        let sizes = [(1, 1), (3, 7), (17, 5), (40, 60)];
+        let rules = ["B3/S23", "B36/S23", "B2/S/C4", "B34-45/S34-58"];
+        for (case, (size, rule)) in sizes.iter().flat_map(|size| rules.map(|rule| (*size, rule))).enumerate() {
+            let seed = case as u64;
+            let soup = Board::random(size, seed, 0.35).cells;
+            let range = if rule.contains('-') { 3 } else { 1 };
+            let boundary = if case % 2 == 0 { Boundary::Toroidal } else { Boundary::Dead };
+            // Chance draws make the order of the rng calls part of the result.
+            let birth_prob = if case % 3 == 0 { 0.8 } else { 1.0 };
+            let configure = |app: App| App { range, boundary, birth_prob, rng: StdRng::seed_from_u64(seed), ..app };
+
+            let mut serial = configure(app_with_board(soup.clone(), rule));
+            let mut expected = Vec::new();
+            for _ in 0..10 {
+                serial.update_game_table();
+                expected.push(serial.board.cells.clone());
+            }
+
+            for threads in [1, 2, 7] {
+                let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+                let mut parallel = App { parallel: true, ..configure(app_with_board(soup.clone(), rule)) };
+                pool.install(|| {
+                    for (generation, expected) in expected.iter().enumerate() {
+                        parallel.update_game_table();
+                        assert!(
+                            parallel.board.cells == *expected,
+                            "{rule} on {size:?} (seed {seed}) with {threads} threads differs at generation {generation}"
+                        );
+                    }
+                });
             }
-            selected_table.push(row);
         }
-        (selected_table, (x_min, y_min))
     }
 
-    fn is_inside_user_cursor(&self, x: usize, y: usize) -> bool {
-        let (x1, y1) = self.game_table_user_cursor;
-        let (x2, y2) = self.game_table_user_cursor2;
-        let x_min = x1.min(x2);
-        let x_max = x1.max(x2);
-        let y_min = y1.min(y2);
-        let y_max = y1.max(y2);
-        x >= x_min && x <= x_max && y >= y_min && y <= y_max
-    }
+    #[test]
+    fn dying_cells_count_down_to_dead() {
+        let mut game_table = Board::empty((5, 5)).cells;
+        game_table[2][2] = ALIVE;
+        let mut app = app_with_board(game_table, "B2/S/C4");
 
-    fn save_selected_table(&self) {
-        if !self.game_pause {
-            return;
+        for expected in [2, 3, DEAD] {
+            app.update_game_table();
+            assert_eq!(app.board.cells[2][2], expected);
         }
+    }
 
-        let (selected_table, _) = self.get_selected_table();
+    #[test]
+    fn dying_cells_are_not_counted_as_neighbours() {
+        let mut game_table = Board::empty((7, 7)).cells;
+        game_table[3][2] = ALIVE;
+        game_table[3][3] = ALIVE;
+        let mut app = app_with_board(game_table, "B2/S/C3");
 
-        let mut bool_buffer: Vec<bool> = Vec::with_capacity(8);
-        let mut buffer_i = 0;
-        let mut row_file:Vec<u8> = Vec::new();
+        app.update_game_table();
+        for (x, y) in [(2, 2), (2, 3), (4, 2), (4, 3)] {
+            assert_eq!(app.board.cells[x][y], ALIVE);
+        }
+        assert_eq!(app.board.cells[3][2], 2);
+        assert_eq!(app.board.cells[3][3], 2);
 
-        row_file.push(selected_table.len() as u8);
-        row_file.push(selected_table[0].len() as u8);
-        for row in selected_table {
-            for cell in row {
-                bool_buffer.push(cell);
-                buffer_i += 1;
-                if buffer_i == 8 {
-                    row_file.push(bool_8_to_u8(&bool_buffer));
-                    buffer_i = 0;
-                    bool_buffer.clear();
-                }
+        // (3, 1) sees two live cells and a dying one: only the live ones count.
+        app.update_game_table();
+        assert_eq!(app.board.cells[3][1], ALIVE);
+        assert_eq!(app.board.cells[3][2], DEAD);
+    }
+
+    /// A glider crossing a board of still lifes, so most cells are skipped.
+    fn glider_among_blocks() -> GameTable {
+        let mut game_table = vec![vec![DEAD; 40]; 40];
+        for (x, y) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            game_table[x][y] = ALIVE;
+        }
+        for corner in [(10, 20), (20, 30), (30, 10), (34, 34)] {
+            for (dx, dy) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                game_table[corner.0 + dx][corner.1 + dy] = ALIVE;
             }
         }
-        if buffer_i != 0 {
-            row_file.push(bool_8_to_u8(&bool_buffer));
+        game_table
+    }
+
+    #[test]
+    fn dirty_cells_update_moves_a_glider() {
+        let mut app = app_with_board(glider_among_blocks(), "B3/S23");
+        let mut expected = app.board.clone();
+        for _ in 0..120 {
+            app.update_game_table();
+            expected = expected.step(&app.rule, app.boundary);
+            assert_eq!(app.board, expected);
+            assert!(app.dirty_cells.len() < 100);
         }
+    }
 
-        std::fs::write("save.data", row_file).unwrap();
+    #[test]
+    fn edits_seed_every_cell_as_dirty() {
+        let mut app = app_with_board(glider_among_blocks(), "B3/S23");
+        for _ in 0..10 {
+            app.update_game_table();
+        }
+        app.record_edit();
+        for y in 5..8 {
+            app.board.cells[25][y] = ALIVE;
+        }
+
+        let mut expected = app.board.clone();
+        for _ in 0..10 {
+            app.update_game_table();
+            expected = expected.step(&app.rule, app.boundary);
+            assert_eq!(app.board, expected);
+        }
     }
 
-    fn import_selected_table(&mut self) {
-        if !self.game_pause {
-            return;
+    #[test]
+    fn clearing_walls_marks_every_cell_dirty() {
+        // The walls keep the pair beside the block alive, so the board is
+        // still; once they go the pair dies.
+        let mut app = app_with_board(vec![vec![DEAD; 12]; 12], "B3/S23");
+        for (x, y) in [(4, 4), (4, 5), (5, 4), (5, 5), (4, 7), (5, 7)] {
+            app.board.cells[x][y] = ALIVE;
+        }
+        app.walls = vec![vec![false; 12]; 12];
+        for (x, y) in [(4, 8), (6, 8)] {
+            app.walls[x][y] = true;
         }
+        for _ in 0..5 {
+            app.update_game_table();
+        }
+        app.clear_walls();
 
-        let vec = std::fs::read("save.data").unwrap();
-        let length = vec[0] as usize;
-        let height = vec[1] as usize;
+        let mut expected = app.board.clone();
+        for _ in 0..10 {
+            app.update_game_table();
+            expected = expected.step(&app.rule, app.boundary);
+            assert_eq!(app.board, expected);
+        }
+    }
 
-        let mut bool_buffer: Vec<bool> = Vec::new();
+    #[test]
+    fn rotating_a_clipboard_swaps_its_dimensions() {
+        let table = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let rotated = rotate_table(&table);
+        assert_eq!(rotated, vec![vec![4, 1], vec![5, 2], vec![6, 3]]);
+        let turned_back = (0..3).fold(rotated, |table, _| rotate_table(&table));
+        assert_eq!(turned_back, table);
+    }
 
-        for i in 2..vec.len() {
-            let bool_vec = u8_to_bool_8(vec[i]);
-            for j in 0..bool_vec.len() {
-                bool_buffer.push(bool_vec[j]);
-            }
+    #[test]
+    fn moving_the_cursor_pans_the_viewport() {
+        let mut app = app_with_board(vec![vec![DEAD; 100]; 50], "B3/S23");
+        app.terminal_size = (20 + App::STATUS_ROWS, 40);
+        let right = KeyEvent::from(KeyCode::Right);
+        for _ in 0..60 {
+            app.game_table_user_cursor_move(KeyCode::Right, right);
         }
-        let (x, y) = self.game_table_user_cursor;
+        assert_eq!(app.game_table_user_cursor.1, 60);
+        assert_eq!(app.viewport_origin.1, 60 + App::VIEWPORT_MARGIN + 1 - 40);
 
-        let mut i = 0;
-        for row in x..(x + length) {
-            for cell in y..(y + height) {
-                self.game_table[row][cell] = bool_buffer[i];
-                i += 1;
+        // Wrapping to the last column takes the view to the right edge.
+        app.game_table_user_cursor.1 = 0;
+        app.game_table_user_cursor_move(KeyCode::Left, KeyEvent::from(KeyCode::Left));
+        assert_eq!(app.viewport_origin, (0, 100 - 40));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn board_image_scales_every_cell() {
+        let mut app = app_with_board(vec![vec![ALIVE, DEAD, DEAD], vec![DEAD, DEAD, DEAD]], "B3/S23");
+        app.walls[1][2] = true;
+        let image = app.board_image(3);
+        assert_eq!(image.dimensions(), (9, 6));
+        assert_eq!(image.get_pixel(2, 2).0, [0, 0, 0]);
+        assert_eq!(image.get_pixel(3, 0).0, [255, 255, 255]);
+        assert_eq!(image.get_pixel(8, 5).0, [230, 180, 0]);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn recording_writes_one_gif_frame_per_generation() {
+        use image::AnimationDecoder;
+
+        let mut app = app_with_board(glider_among_blocks(), "B3/S23");
+        app.toggle_recording();
+        for _ in 0..4 {
+            app.step();
+        }
+        let frames = app.recording.take().unwrap();
+        assert_eq!(frames.len(), 5);
+
+        let path = std::env::temp_dir().join(format!("recording-test-{}.gif", std::process::id()));
+        app.write_gif(&path, &frames).unwrap();
+        let decoder = image::codecs::gif::GifDecoder::new(io::BufReader::new(std::fs::File::open(&path).unwrap())).unwrap();
+        let decoded = decoder.into_frames().collect_frames().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(decoded.len(), 5);
+        assert_eq!(decoded[0].buffer().dimensions(), (40 * App::GIF_SCALE, 40 * App::GIF_SCALE));
+    }
+
+    #[test]
+    fn atomic_writes_leave_no_temporary_file() {
+        let directory = std::env::temp_dir().join(format!("autosave-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(App::AUTOSAVE_FILE);
+        formats::write_atomically(&path, "first").unwrap();
+        formats::write_atomically(&path, "second").unwrap();
+        let files: Vec<_> = std::fs::read_dir(&directory).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(contents, "second");
+        assert_eq!(files, [App::AUTOSAVE_FILE]);
+    }
+
+    #[test]
+    fn lines_have_no_gaps() {
+        assert_eq!(line_cells((2, 2), (2, 2)), [(2, 2)]);
+        assert_eq!(line_cells((0, 0), (2, 5)), [(0, 0), (0, 1), (1, 2), (1, 3), (2, 4), (2, 5)]);
+        let line = line_cells((7, 1), (0, 4));
+        assert_eq!((line[0], line[line.len() - 1]), ((7, 1), (0, 4)));
+        assert!(line.windows(2).all(|pair| pair[0].0.abs_diff(pair[1].0) <= 1 && pair[0].1.abs_diff(pair[1].1) <= 1));
+    }
+
+    #[test]
+    fn stop_at_pauses_once_and_clears_on_resume() {
+        let mut app = app_with_board(glider_among_blocks(), "B3/S23");
+        app.game_pause = false;
+        app.stop_at = Some(3);
+        app.fast_forward_count = 10;
+        app.fast_forward();
+        assert_eq!(app.generation, 3);
+        assert!(app.game_pause);
+
+        app.toggle_game_pause();
+        assert_eq!(app.stop_at, None);
+        app.step();
+        assert!(!app.check_stop_at());
+    }
+
+    #[test]
+    fn hex_colors_parse_or_leave_the_color_alone() {
+        assert_eq!(parse_hex_color("#1e90FF"), Ok(Color::Rgb(0x1e, 0x90, 0xff)));
+        assert_eq!(parse_hex_color("00ff00"), Ok(Color::Rgb(0, 255, 0)));
+        for input in ["", "#12345", "#1234567", "#12345g", "#ééé"] {
+            assert!(parse_hex_color(input).is_err(), "{input}");
+        }
+
+        let mut app = App::default();
+        app.submit_prompt(Prompt { kind: PromptKind::AliveColor, input: "#zzzzzz".to_string() });
+        assert!(app.theme == Theme::PRESETS[0]);
+        assert!(app.notice.is_some());
+    }
+
+    #[test]
+    fn longevity_stats_follow_the_run_and_reset_on_edit() {
+        // A blinker next to a cell that dies right away.
+        let mut game_table = vec![vec![DEAD; 8]; 8];
+        for (x, y) in [(2, 1), (2, 2), (2, 3), (6, 6)] {
+            game_table[x][y] = ALIVE;
+        }
+        let mut app = app_with_board(game_table, "B3/S23");
+        for _ in 0..4 {
+            app.step();
+        }
+        assert_eq!(app.detected_period, Some(2));
+        assert_eq!(app.stabilized_at, Some(1));
+        assert_eq!(app.peak_population, Some((3, 1)));
+
+        app.record_edit();
+        assert_eq!((app.stabilized_at, app.peak_population), (None, None));
+    }
+
+    #[test]
+    fn symmetric_images_stay_in_the_grid() {
+        assert_eq!(Symmetry::Horizontal.images((1, 2), (5, 7)), [(1, 2), (1, 4)]);
+        assert_eq!(Symmetry::Both.images((1, 2), (5, 7)), [(1, 2), (1, 4), (3, 2), (3, 4)]);
+        // The center row is its own mirror image.
+        assert_eq!(Symmetry::Vertical.images((2, 0), (5, 7)), [(2, 0)]);
+        assert_eq!(Symmetry::FourFold.images((0, 1), (4, 4)), [(0, 1), (1, 3), (2, 0), (3, 2)]);
+        // On a 4x6 grid some quarter turns land off the grid or between
+        // cells and are dropped.
+        assert!(Symmetry::FourFold.images((0, 0), (4, 6)).iter().all(|&(x, y)| x < 4 && y < 6));
+
+        let mut app = app_with_board(vec![vec![DEAD; 5]; 5], "B3/S23");
+        app.game_pause = true;
+        app.symmetry = Symmetry::Both;
+        app.game_table_user_cursor = (2, 1);
+        app.switch_cell_state();
+        assert_eq!(app.population(), 2);
+        assert_eq!((app.board.cells[2][1], app.board.cells[2][3]), (ALIVE, ALIVE));
+    }
+
+    #[test]
+    fn clearing_the_board_needs_a_second_n() {
+        let mut app = app_with_board(glider_among_blocks(), "B3/S23");
+        let population = app.population();
+        let n = KeyEvent::from(KeyCode::Char('n'));
+        app.handle_key_event(n);
+        assert_eq!(app.population(), population);
+
+        // Any other key in between cancels the reset.
+        app.handle_key_event(KeyEvent::from(KeyCode::Char('v')));
+        app.handle_key_event(n);
+        assert_eq!(app.population(), population);
+
+        app.handle_key_event(n);
+        assert_eq!(app.population(), 0);
+    }
+
+    #[test]
+    fn plaintext_export_keeps_only_the_live_cells() {
+        let mut game_table = vec![vec![DEAD; 10]; 8];
+        for (x, y) in [(2, 4), (3, 5), (4, 3), (4, 4), (4, 5)] {
+            game_table[x][y] = ALIVE;
+        }
+        let text = formats::plaintext_encode(&game_table, '#', '.').unwrap();
+        assert_eq!(text, "! 3x3\n.#.\n..#\n###\n");
+        assert_eq!(formats::plaintext_encode(&vec![vec![DEAD; 4]; 4], '#', '.'), None);
+
+        let cells = formats::plaintext_encode(&game_table, 'O', '.').unwrap();
+        assert_eq!(formats::parse_cells(&cells).unwrap(), formats::parse_cells(".O.\n..O\nOOO").unwrap());
+    }
+
+    #[test]
+    fn oriented_gliders_fly_their_way() {
+        for (name, orientation) in Orientation::GLIDER_DIRECTIONS {
+            let mut app = app_with_board(vec![vec![DEAD; 20]; 20], "B3/S23");
+            app.place_pattern(&GLIDER, (8, 8), orientation);
+            let (start, _) = formats::bounding_box(&app.board.cells).unwrap();
+            for _ in 0..4 {
+                app.update_game_table();
             }
+            let (end, _) = formats::bounding_box(&app.board.cells).unwrap();
+            let heading = (end.0 as i32 - start.0 as i32, end.1 as i32 - start.1 as i32);
+            let expected = match name {
+                "South-east" => (1, 1),
+                "South-west" => (1, -1),
+                "North-west" => (-1, -1),
+                _ => (-1, 1),
+            };
+            assert_eq!(heading, expected, "{name}");
         }
+
+        let flipped = Orientation { quarter_turns: 0, flipped: true };
+        let glider_flipped: GameTable = vec![vec![DEAD, ALIVE, DEAD], vec![ALIVE, DEAD, DEAD], vec![ALIVE, ALIVE, ALIVE]];
+        assert_eq!(flipped.apply(GLIDER.cells), glider_flipped);
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    #[test]
+    fn column_histogram_scales_to_the_fullest_column() {
+        let mut game_table = vec![vec![DEAD; 4]; 8];
+        for (x, row) in game_table.iter_mut().enumerate() {
+            row[1] = ALIVE;
+            row[3] = if x < 2 { ALIVE } else { DEAD };
+        }
+        game_table[0][2] = ALIVE;
+        let app = app_with_board(game_table, "B3/S23");
+        let bars = app.column_histogram(Rect::new(0, 0, 4, 1)).to_string();
+        assert_eq!(bars, " █▁▂");
     }
-}
 
-impl Widget for &App {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let game_table_printed = self.print_game_table();
-        Paragraph::new(game_table_printed)
-            .render(area, buf)
+    #[test]
+    fn glider_gun_demo_needs_room() {
+        let mut app = app_with_board(vec![vec![ALIVE; 30]; 10], "B36/S23");
+        app.game_pause = true;
+        app.launch_glider_gun_demo();
+        assert!(app.notice.is_some());
+        assert!(app.game_pause);
+        assert_eq!(app.population(), 300);
+
+        let mut app = app_with_board(vec![vec![ALIVE; 60]; 40], "B36/S23");
+        app.launch_glider_gun_demo();
+        assert!(!app.game_pause);
+        assert!(app.rule == Rule::CONWAY);
+        let population = app.population();
+        for _ in 0..30 {
+            app.update_game_table();
+        }
+        // One period later the gun is back, plus one glider.
+        assert_eq!(app.population(), population + 5);
     }
-}
 
-fn initialize_game_table(size: (usize, usize)) -> GameTable {
-    let mut game_table: GameTable = Vec::new();
-    for _ in 0..size.0 {
-        let mut row: Vec<bool> = Vec::new();
-        for _ in 0..size.1 {
-            let rng_bool: bool = rand::rng().random();
-            row.push(rng_bool);
+    #[test]
+    fn undo_after_the_glider_gun_demo_brings_the_board_back() {
+        let board = glider_among_blocks();
+        let mut app = app_with_board(board.clone(), "B3/S23");
+        app.launch_glider_gun_demo();
+        assert_ne!(app.board.cells, board);
+
+        app.undo();
+        assert_eq!(app.board.cells, board);
+    }
+
+    #[test]
+    fn resizing_keeps_the_top_left_cells() {
+        let mut game_table = vec![vec![DEAD; 10]; 10];
+        for (x, y) in [(1, 1), (1, 2), (2, 1), (2, 2)] {
+            game_table[x][y] = ALIVE;
         }
-        game_table.push(row);
+        game_table[9][9] = ALIVE;
+        let block = game_table.clone();
+        let mut app = app_with_board(game_table, "B3/S23");
+        app.game_table_user_cursor = (9, 9);
+
+        app.resize_grid((20, 30));
+        assert_eq!(app.board.size, (20, 30));
+        assert_eq!(app.walls.len(), 20);
+        assert_eq!(app.population(), 5);
+        assert!(app.board.cells[..10].iter().zip(&block).all(|(row, old)| row[..10] == old[..]));
+        assert!(app.board.cells[10..].iter().flatten().all(|cell| *cell == DEAD));
+
+        // Shrinking drops the far corner cell and pulls the cursor in.
+        app.resize_grid((5, 6));
+        assert_eq!(app.board.size, (5, 6));
+        assert!(app.board.cells.iter().all(|row| row.len() == 6));
+        assert_eq!(app.population(), 4);
+        assert_eq!(app.game_table_user_cursor, (4, 5));
+
+        let mut expected = app.board.clone();
+        app.update_game_table();
+        expected = expected.step(&app.rule, app.boundary);
+        assert_eq!(app.board, expected);
     }
 
-    game_table
-}
+    #[test]
+    fn digits_before_t_set_the_step_count_while_paused() {
+        let mut app = app_with_board(glider_among_blocks(), "B3/S23");
+        app.game_pause = true;
+        for character in ['0', '1', '2', 't'] {
+            app.handle_key_event(KeyEvent::from(KeyCode::Char(character)));
+        }
+        assert_eq!((app.step_by_step_next, app.step_by_step_count), (true, 12));
+        assert_eq!(app.count_prefix, None);
+
+        // Any other key drops the count.
+        app.handle_key_event(KeyEvent::from(KeyCode::Char('5')));
+        app.handle_key_event(KeyEvent::from(KeyCode::Char('v')));
+        app.handle_key_event(KeyEvent::from(KeyCode::Char('t')));
+        assert_eq!(app.step_by_step_count, 1);
+
+        // While running, digits still pick a speed preset.
+        app.game_pause = false;
+        app.handle_key_event(KeyEvent::from(KeyCode::Char('9')));
+        assert_eq!(app.update_per_second_max, App::SPEED_PRESETS[8]);
+    }
+
+    #[test]
+    fn a_tiny_terminal_is_too_small_for_the_board() {
+        let mut app = App { terminal_size: (App::STATUS_ROWS + 2, 40), ..App::default() };
+        assert!(app.terminal_too_small());
+        app.terminal_size = (App::STATUS_ROWS + 3, 40);
+        assert!(!app.terminal_too_small());
+        app.terminal_size = (30, 2);
+        assert!(app.terminal_too_small());
+    }
+
+    #[test]
+    fn a_saved_session_loads_back() {
+        let mut app = app_with_board(vec![vec![DEAD; 12]; 10], "B36/S23");
+        app.place_pattern(&GLIDER, (2, 3), Orientation::default());
+        app.boundary = Boundary::Dead;
+        app.game_table_user_cursor = (4, 5);
+        app.generation = 7;
+        let path = std::env::temp_dir().join(format!("session-test-{}.json", std::process::id()));
+        app.save_session(&path).unwrap();
+
+        let mut loaded = App { requested_grid_size: (Some(10), Some(12)), ..App::default() };
+        loaded.load_session(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.board, app.board);
+        assert_eq!(loaded.rule.to_string(), "B36/S23");
+        assert!(loaded.boundary == Boundary::Dead);
+        assert_eq!(loaded.game_table_user_cursor, (4, 5));
+        assert_eq!(loaded.generation, 7);
+    }
+
+    #[test]
+    fn a_session_without_settings_keeps_the_current_ones() {
+        let path = std::env::temp_dir().join(format!("old-session-test-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"cells": [[0, 1, 0], [0, 1, 0], [0, 1, 0]], "comment": "unknown"}"#).unwrap();
+        let mut app = App { requested_grid_size: (Some(5), Some(5)), ..App::default() };
+        app.update_per_second_max = 42.0;
+        app.load_session(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(app.board.size, (5, 5));
+        assert_eq!(app.population(), 3);
+        assert_eq!(app.update_per_second_max, 42.0);
+    }
+
+    #[test]
+    fn the_speed_ramp_eases_back_to_the_default_rate() {
+        let mut app = App::default();
+        let pressed_at = Instant::now();
+        app.ramp_pressed_at = Some(pressed_at);
+        let frame = Duration::from_millis(100);
+        app.ramp_update_per_second_max(pressed_at + frame, frame);
+        assert!(app.update_per_second_max > App::DEFAULT_MAX_UPDATE_PER_SECOND);
+
+        let released = pressed_at + App::RAMP_HOLD;
+        app.ramp_update_per_second_max(released, Duration::from_secs(1));
+        assert_eq!(app.update_per_second_max, App::DEFAULT_MAX_UPDATE_PER_SECOND);
+        assert!(app.ramp_pressed_at.is_none());
+    }
 
-fn initialize_empty_game_table(size: (usize, usize)) -> GameTable {
-    let mut game_table: GameTable = Vec::new();
-    for _ in 0..size.0 {
-        let mut row: Vec<bool> = Vec::new();
-        for _ in 0..size.1 {
-            row.push(false);
+    #[test]
+    fn cells_that_just_died_are_tracked_for_one_generation() {
+        let mut blinker = vec![vec![DEAD; 5]; 5];
+        blinker[2][1..4].fill(ALIVE);
+        let mut app = app_with_board(blinker, "B3/S23");
+        app.smooth_death = true;
+        app.update_game_table();
+        assert!(app.just_died[2][1] && app.just_died[2][3] && !app.just_died[2][2]);
+        assert_eq!(app.population(), 3);
+        app.update_game_table();
+        assert!(!app.just_died[2][1] && app.just_died[1][2] && app.just_died[3][2]);
+    }
+
+    #[test]
+    fn update_time_percentiles_cover_the_last_window() {
+        let mut app = App::default();
+        assert!(app.update_time_percentiles().is_none());
+        for millis in 0..200 {
+            app.record_update_time(Duration::from_millis(millis));
         }
-        game_table.push(row);
+        assert_eq!(app.update_times.len(), App::UPDATE_TIMES_WINDOW);
+        let (min, median, p99) = app.update_time_percentiles().unwrap();
+        assert_eq!(min, Duration::from_millis(80));
+        assert_eq!(median, Duration::from_millis(140));
+        assert_eq!(p99, Duration::from_millis(198));
     }
 
-    game_table
-}
+    #[test]
+    fn a_random_soup_stays_inside_the_selection() {
+        let mut app = app_with_board(vec![vec![DEAD; 10]; 10], "B3/S23");
+        app.game_pause = true;
+        app.fill_density = 0.5;
+        app.selection_start = Some((2, 3));
+        app.game_table_user_cursor = (5, 7);
+        app.fill_selection_randomly();
+        let inside = app.board.cells[2..=5].iter().map(|row| row[3..=7].iter().filter(|cell| **cell == ALIVE).count());
+        assert_eq!(inside.sum::<usize>(), app.population());
+        assert!(app.population() > 0);
+    }
 
-fn bool_8_to_u8(vec: &Vec<bool>) -> u8 {
-    let mut u8_buffer: u8 = 0;
-    for i in 0..vec.len() {
-        u8_buffer |= u8::from(vec[i]) << (7 - i)
+    #[test]
+    fn life_without_death_only_grows_until_it_freezes() {
+        // An R-pentomino, which grows into a frozen blob without death.
+        let mut game_table = vec![vec![DEAD; 12]; 12];
+        for (x, y) in [(4, 5), (4, 6), (5, 4), (5, 5), (6, 5)] {
+            game_table[x][y] = ALIVE;
+        }
+        let mut app = app_with_board(game_table, "B3/S012345678");
+        app.boundary = Boundary::Dead;
+        let mut population = app.population();
+        for _ in 0..40 {
+            app.step();
+            assert!(app.population() >= population);
+            population = app.population();
+        }
+        assert!(!app.game_pause);
+        assert_eq!(app.detected_period, Some(1));
+        assert!(app.stabilized_at.is_some());
     }
 
-    u8_buffer
-}
+    #[test]
+    fn stepping_an_empty_board_is_no_extinction() {
+        let mut app = app_with_board(vec![vec![DEAD; 5]; 5], "B3/S012345678");
+        app.step();
+        assert!(!app.game_pause);
+        assert!(app.notice.is_none());
+    }
 
-fn u8_to_bool_8(u: u8) -> Vec<bool> {
-    let mut bool_buffer: Vec<bool> = Vec::with_capacity(8);
-    for i in 0..8 {
-        bool_buffer.push(bool::from(u >> (7 - i) & 1 == 1));
+    #[test]
+    fn fitting_the_view_centers_the_pattern() {
+        let mut app = app_with_board(vec![vec![DEAD; 100]; 50], "B3/S23");
+        app.terminal_size = (10 + App::STATUS_ROWS, 20);
+        app.place_pattern(&GLIDER, (30, 70), Orientation::default());
+        app.fit_view_to_pattern();
+        assert_eq!(app.viewport_origin, (31 - 5, 71 - 10));
+
+        app.board = Board::empty(app.board.size);
+        app.fit_view_to_pattern();
+        assert_eq!(app.viewport_origin, (0, 0));
     }
-    bool_buffer
-}
\ No newline at end of file
+
+    #[test]
+    fn the_exit_dump_keeps_the_whole_board() {
+        let mut game_table = vec![vec![DEAD; 4]; 3];
+        game_table[1][2] = ALIVE;
+        assert_eq!(formats::plaintext_rows(&game_table, '#', '.'), "....\n..#.\n....\n");
+    }
+
+    #[test]
+    fn undo_after_loading_a_session_keeps_the_board_size() {
+        let path = std::env::temp_dir().join(format!("resized-session-test-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"cells": [[0, 1, 0], [0, 1, 0], [0, 1, 0]]}"#).unwrap();
+        let mut app = app_with_board(vec![vec![DEAD; 5]; 5], "B3/S23");
+        app.requested_grid_size = (Some(5), Some(5));
+        app.grow_grid();
+        app.toggle_cell(0, 0);
+        app.load_session(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        app.undo();
+        assert_eq!(app.board.cells.len(), app.board.size.0);
+        app.step();
+    }
+
+    #[test]
+    fn a_tiny_terminal_still_starts_a_minimal_board() {
+        let mut app = App { terminal_size: (App::STATUS_ROWS, 40), ..App::default() };
+        app.start_board(app.world_size());
+        assert_eq!(app.board.size, (App::MIN_GRID_SIZE, 40));
+        app.step();
+        app.fast_forward();
+    }
+
+    #[test]
+    fn layout_changes_keep_a_grown_grid() {
+        let mut app = app_with_board(vec![vec![DEAD; 20]; 10], "B3/S23");
+        app.terminal_size = (10 + App::STATUS_ROWS, 20);
+        app.grow_grid();
+        let grown = app.board.size;
+        app.board.cells[grown.0 - 1][grown.1 - 1] = ALIVE;
+        app.toggle_status();
+        app.toggle_aspect_correct();
+        app.handle_resize(30, 15);
+        assert_eq!(app.board.size, grown);
+        assert_eq!(app.population(), 1);
+    }
+
+    #[test]
+    fn the_square_world_keeps_the_pattern() {
+        let mut app = app_with_board(vec![vec![DEAD; 20]; 10], "B3/S23");
+        app.terminal_size = (10 + App::STATUS_ROWS, 20);
+        app.place_pattern(&GLIDER, (2, 3), Orientation::default());
+        let board = app.board.clone();
+        app.toggle_square_world();
+        assert_eq!(app.board.size, (10, 10));
+        assert_eq!(app.board.cells, board.cells.iter().map(|row| row[..10].to_vec()).collect::<GameTable>());
+        app.toggle_square_world();
+        assert_eq!(app.board.size, (10, 20));
+        assert_eq!(app.population(), board.population());
+    }
+
+    #[test]
+    fn a_random_soup_without_a_selection_fills_the_board() {
+        let mut app = App { fill_density: 1.0, ..app_with_board(vec![vec![DEAD; 10]; 10], "B3/S23") };
+        app.game_pause = true;
+        let seed = app.seed;
+        app.fill_selection_randomly();
+        assert_eq!((app.population(), app.seed), (100, seed));
+
+        app.undo();
+        assert_eq!(app.population(), 0);
+    }
+
+    #[test]
+    fn random_order_is_reproducible_from_the_seed() {
+        let soup = Board::random((12, 12), 3, 0.4).cells;
+        let run = |seed: u64| {
+            let mut app = App {
+                update_order: UpdateOrder::Random,
+                rng: StdRng::seed_from_u64(seed),
+                ..app_with_board(soup.clone(), "B3/S23")
+            };
+            for _ in 0..5 {
+                app.step();
+            }
+            app.board
+        };
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn kernels_of_ones_run_life() {
+        // With a zero center the sum is the Moore count, so the kernel
+        // states B3/S23 directly. With the center counted a live cell's sum
+        // is one more than its neighbours: survival moves to 3 or 4.
+        const ZERO_CENTER: &str = "1 1 1\n1 0 1\n1 1 1\nbirth 3 3\nsurvive 2 3\n";
+        const ALL_ONES: &str = "1 1 1\n1 1 1\n1 1 1\nbirth 3 3\nsurvive 3 4\n";
+        let soup = Board::random((16, 24), 9, 0.35).cells;
+        for kernel in [ZERO_CENTER, ALL_ONES] {
+            for boundary in [Boundary::Toroidal, Boundary::Dead] {
+                let life = app_with_board(soup.clone(), "B3/S23");
+                let mut life = App { rule: Rule::CONWAY, boundary, ..life };
+                let weighted = app_with_board(soup.clone(), "B3/S23");
+                let mut weighted = App { rule: Rule::CONWAY, boundary, kernel: Some(Kernel::parse(kernel).unwrap()), ..weighted };
+                for _ in 0..10 {
+                    life.update_game_table();
+                    weighted.update_game_table();
+                    assert_eq!(weighted.board, life.board);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_full_history_still_undoes_the_last_step() {
+        let mut app = app_with_board(glider_among_blocks(), "B3/S23");
+        let mut boards = Vec::new();
+        for _ in 0..App::HISTORY_LENGTH + 10 {
+            boards.push(app.board.cells.clone());
+            app.step();
+        }
+        assert_eq!(app.history.len(), App::HISTORY_LENGTH);
+
+        for board in boards.iter().rev().take(3) {
+            app.undo();
+            assert_eq!(&app.board.cells, board);
+        }
+        // The step reuses a dropped redo entry for its history entry.
+        app.redo();
+        app.step();
+        assert_eq!(Some(&app.board.cells), boards.last());
+        assert_eq!(app.history.back().map(|(cells, _)| cells), boards.iter().rev().nth(1));
+    }
+}
@@ -0,0 +1,72 @@
+use crate::life::{Cell, ALIVE, DEAD};
+
+/// Cell shorthands borrowed from RLE: `O` is alive, `B` is dead.
+const O: Cell = ALIVE;
+const B: Cell = DEAD;
+
+pub struct Pattern {
+    pub name: &'static str,
+    pub cells: &'static [&'static [Cell]],
+}
+
+pub const GLIDER: Pattern = Pattern {
+    name: "Glider",
+    cells: &[
+        &[B, O, B],
+        &[B, B, O],
+        &[O, O, O],
+    ],
+};
+
+pub const LWSS: Pattern = Pattern {
+    name: "Lightweight spaceship (LWSS)",
+    cells: &[
+        &[B, O, B, B, O],
+        &[O, B, B, B, B],
+        &[O, B, B, B, O],
+        &[O, O, O, O, B],
+    ],
+};
+
+pub const BLINKER: Pattern = Pattern {
+    name: "Blinker",
+    cells: &[
+        &[O, O, O],
+    ],
+};
+
+pub const PULSAR: Pattern = Pattern {
+    name: "Pulsar",
+    cells: &[
+        &[B, B, O, O, O, B, B, B, O, O, O, B, B],
+        &[B, B, B, B, B, B, B, B, B, B, B, B, B],
+        &[O, B, B, B, B, O, B, O, B, B, B, B, O],
+        &[O, B, B, B, B, O, B, O, B, B, B, B, O],
+        &[O, B, B, B, B, O, B, O, B, B, B, B, O],
+        &[B, B, O, O, O, B, B, B, O, O, O, B, B],
+        &[B, B, B, B, B, B, B, B, B, B, B, B, B],
+        &[B, B, O, O, O, B, B, B, O, O, O, B, B],
+        &[O, B, B, B, B, O, B, O, B, B, B, B, O],
+        &[O, B, B, B, B, O, B, O, B, B, B, B, O],
+        &[O, B, B, B, B, O, B, O, B, B, B, B, O],
+        &[B, B, B, B, B, B, B, B, B, B, B, B, B],
+        &[B, B, O, O, O, B, B, B, O, O, O, B, B],
+    ],
+};
+
+pub const GOSPER_GLIDER_GUN: Pattern = Pattern {
+    name: "Gosper glider gun",
+    cells: &[
+        &[B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, O, B, B, B, B, B, B, B, B, B, B, B],
+        &[B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, O, B, O, B, B, B, B, B, B, B, B, B, B, B],
+        &[B, B, B, B, B, B, B, B, B, B, B, B, O, O, B, B, B, B, B, B, O, O, B, B, B, B, B, B, B, B, B, B, B, B, O, O],
+        &[B, B, B, B, B, B, B, B, B, B, B, O, B, B, B, O, B, B, B, B, O, O, B, B, B, B, B, B, B, B, B, B, B, B, O, O],
+        &[O, O, B, B, B, B, B, B, B, B, O, B, B, B, B, B, O, B, B, B, O, O, B, B, B, B, B, B, B, B, B, B, B, B, B, B],
+        &[O, O, B, B, B, B, B, B, B, B, O, B, B, B, O, B, O, O, B, B, B, B, O, B, O, B, B, B, B, B, B, B, B, B, B, B],
+        &[B, B, B, B, B, B, B, B, B, B, O, B, B, B, B, B, O, B, B, B, B, B, B, B, O, B, B, B, B, B, B, B, B, B, B, B],
+        &[B, B, B, B, B, B, B, B, B, B, B, O, B, B, B, O, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B],
+        &[B, B, B, B, B, B, B, B, B, B, B, B, O, O, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B, B],
+    ],
+};
+
+pub const PATTERNS: &[Pattern] = &[GLIDER, LWSS, BLINKER, PULSAR, GOSPER_GLIDER_GUN];
@@ -0,0 +1,23 @@
+use crate::life::GameTable;
+use serde::{Deserialize, Serialize};
+
+/// Everything `<Ctrl-s>` saves to resume a session later: the board and
+/// the settings that shape how it evolves and looks.
+///
+/// Unknown fields are ignored and missing settings keep their current
+/// value, so files written by other versions still load. Only the cells
+/// are required.
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    pub cells: GameTable,
+    #[serde(default)]
+    pub generation: u64,
+    pub rule: Option<String>,
+    /// `Boundary::name`.
+    pub boundary: Option<String>,
+    pub range: Option<i32>,
+    pub update_per_second_max: Option<f64>,
+    /// `Theme::name`.
+    pub theme: Option<String>,
+    pub cursor: Option<(usize, usize)>,
+}